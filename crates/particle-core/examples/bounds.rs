@@ -0,0 +1,117 @@
+//! Prints the wrap, bounce, clamp and kill modes of the world bounds
+//!
+//! The edge behaviour is asserted by the tests in `bounds.rs`.
+//! Run with: cargo run --example bounds
+
+use glam::Vec2;
+use particle_core::{
+    Burst, EdgeMode, Emitter, ParticleConfig, ParticleSystem, SubEmitter, SubEmitterTrigger,
+    WorldBounds, AABB,
+};
+
+const DT: f32 = 1.0 / 60.0;
+
+/// Weightless system confined to a 100×100 box
+fn boxed(left: EdgeMode, right: EdgeMode, top: EdgeMode, bottom: EdgeMode) -> ParticleSystem {
+    ParticleSystem::with_config(ParticleConfig {
+        seed: Some(2),
+        gravity: Vec2::ZERO,
+        drag_coefficient: 0.0,
+        bounds: Some(WorldBounds {
+            area: AABB::new(Vec2::ZERO, Vec2::splat(100.0)),
+            left,
+            right,
+            top,
+            bottom,
+        }),
+        ..ParticleConfig::default()
+    })
+}
+
+/// Emitter firing `count` particles at once with the given velocity
+fn shot(position: Vec2, velocity: Vec2, count: u32) -> Emitter {
+    let mut emitter = Emitter::new(position);
+    emitter.settings.rate = 0.0;
+    emitter.settings.spread = 0.0;
+    emitter.settings.direction = velocity.y.atan2(velocity.x);
+    emitter.settings.initial_velocity = velocity.length();
+    emitter.settings.particle_lifetime = 10.0;
+    emitter.settings.bursts.push(Burst::new(0.0, count));
+    emitter
+}
+
+fn main() {
+    println!("📦 World bounds\n");
+
+    // Wrapping carries particles across and keeps their trail short
+    let mut system = boxed(
+        EdgeMode::Wrap,
+        EdgeMode::Wrap,
+        EdgeMode::Kill,
+        EdgeMode::Kill,
+    );
+    system.add_emitter(shot(Vec2::new(50.0, 50.0), Vec2::new(600.0, 0.0), 1));
+    for _ in 0..60 {
+        system.update(DT, &[]);
+    }
+    // Spawned at the end of the first update, it then moves 590 units
+    let x = system.particles.iter().next().unwrap().position[0];
+    println!("Wrapped:   x = {:.1}", x);
+
+    // Bouncing loses speed into the floor and along it
+    let bounce = EdgeMode::Bounce {
+        restitution: 0.5,
+        friction: 0.25,
+    };
+    let mut system = boxed(bounce, bounce, bounce, bounce);
+    system.add_emitter(shot(Vec2::new(50.0, 90.0), Vec2::new(60.0, 120.0), 1));
+    for _ in 0..10 {
+        system.update(DT, &[]);
+    }
+    let particle = system.particles.iter().next().unwrap();
+    println!("Bounced:   velocity {:?}", particle.vel());
+
+    // Clamped particles stop at the edge and slide along it
+    let mut system = boxed(
+        EdgeMode::Clamp,
+        EdgeMode::Clamp,
+        EdgeMode::Clamp,
+        EdgeMode::Clamp,
+    );
+    system.add_emitter(shot(Vec2::new(50.0, 50.0), Vec2::new(300.0, 300.0), 1));
+    for _ in 0..30 {
+        system.update(DT, &[]);
+    }
+    let particle = system.particles.iter().next().unwrap();
+    println!("Clamped:   {:?}", particle.pos());
+
+    // Killed particles free their budget and fire death sub-emitters
+    let mut system = boxed(
+        EdgeMode::Kill,
+        EdgeMode::Kill,
+        EdgeMode::Kill,
+        EdgeMode::Kill,
+    );
+    let mut sparks = Emitter::new(Vec2::ZERO);
+    sparks.settings.enabled = false;
+    sparks.settings.particle_lifetime = 0.01;
+    let sparks = system.add_emitter(sparks).unwrap();
+    let mut burst = shot(Vec2::new(50.0, 50.0), Vec2::new(0.0, -300.0), 20);
+    burst.settings.spread = 1.0;
+    burst.settings.sub_emitters.push(SubEmitter::new(
+        SubEmitterTrigger::Death,
+        system.emitter_index(sparks).unwrap(),
+        1,
+    ));
+    system.add_emitter(burst);
+    let mut spark_count = 0;
+    for _ in 0..30 {
+        system.update(DT, &[]);
+        spark_count += system.stats().emitter_particles[0];
+    }
+    println!(
+        "Killed:    {} left, {} sparks",
+        system.particle_count(),
+        spark_count
+    );
+}
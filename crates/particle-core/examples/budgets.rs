@@ -0,0 +1,94 @@
+//! Prints how overflow policies share the particle budget
+//!
+//! The sharing rules are asserted by the tests in `particles.rs`.
+//! Run with: cargo run --example budgets
+
+use glam::Vec2;
+use particle_core::{Emitter, OverflowPolicy, ParticleConfig, ParticleSystem};
+
+const DT: f32 = 1.0 / 60.0;
+const MAX_PARTICLES: usize = 1000;
+
+/// Emitter spawning long-lived particles far faster than the budget allows
+fn flood(x: f32) -> Emitter {
+    let mut emitter = Emitter::new(Vec2::new(x, 0.0));
+    emitter.settings.rate = 6000.0;
+    emitter.settings.particle_lifetime = 60.0;
+    emitter
+}
+
+fn system(overflow: OverflowPolicy) -> ParticleSystem {
+    ParticleSystem::with_config(ParticleConfig {
+        max_particles: MAX_PARTICLES,
+        seed: Some(11),
+        overflow,
+        ..ParticleConfig::default()
+    })
+}
+
+fn count_from(system: &ParticleSystem, emitter: u32) -> usize {
+    system
+        .particles
+        .emitters()
+        .iter()
+        .filter(|&&e| e == emitter)
+        .count()
+}
+
+fn run(system: &mut ParticleSystem, steps: usize) {
+    for _ in 0..steps {
+        system.update(DT, &[]);
+    }
+}
+
+fn main() {
+    println!("🪣 Particle budgets of {} particles\n", MAX_PARTICLES);
+
+    // A newly added emitter takes its share from a long-running one
+    let mut recycling = system(OverflowPolicy::RecycleOldest);
+    recycling.add_emitter(flood(0.0));
+    run(&mut recycling, 60);
+    recycling.add_emitter(flood(100.0));
+    run(&mut recycling, 60);
+    println!(
+        "Recycle oldest: {} / {} particles",
+        count_from(&recycling, 0),
+        count_from(&recycling, 1)
+    );
+
+    // Weights skew the shares
+    recycling.emitters[1].settings.weight = 3.0;
+    run(&mut recycling, 60);
+    println!(
+        "Weighted 1:3:   {} / {} particles",
+        count_from(&recycling, 0),
+        count_from(&recycling, 1)
+    );
+
+    // Rejecting keeps the oldest particles and counts the drops
+    let mut rejecting = system(OverflowPolicy::Reject);
+    rejecting.add_emitter(flood(0.0));
+    run(&mut rejecting, 60);
+    rejecting.add_emitter(flood(100.0));
+    run(&mut rejecting, 1);
+    println!(
+        "Reject:         {} / {} dropped",
+        rejecting.emitters[0].dropped(),
+        rejecting.emitters[1].dropped()
+    );
+
+    // Per-emitter budgets hold regardless of free room
+    for overflow in [OverflowPolicy::Reject, OverflowPolicy::RecycleShortestLife] {
+        let mut capped = system(overflow);
+        let mut emitter = flood(0.0);
+        emitter.settings.max_particles = Some(100);
+        capped.add_emitter(emitter);
+        run(&mut capped, 30);
+        println!(
+            "Budget of 100:  {} particles, {} dropped ({:?})",
+            capped.particle_count(),
+            capped.emitters[0].dropped(),
+            overflow
+        );
+    }
+}
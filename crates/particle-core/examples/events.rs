@@ -0,0 +1,64 @@
+//! Prints the spawn, death, collision and zone events recorded by updates
+//!
+//! What each event reports is asserted by the tests in `events.rs`.
+//! Run with: cargo run --example events
+
+use glam::Vec2;
+use particle_core::{Emitter, Outline, ParticleConfig, ParticleEvent, ParticleSystem};
+
+const DT: f32 = 1.0 / 60.0;
+
+/// Axis-aligned box as an outline
+fn rectangle(min: Vec2, max: Vec2) -> Outline {
+    Outline::from_points(vec![
+        min,
+        Vec2::new(max.x, min.y),
+        max,
+        Vec2::new(min.x, max.y),
+    ])
+}
+
+fn main() {
+    println!("📣 Simulation events\n");
+
+    let mut system = ParticleSystem::with_config(ParticleConfig {
+        seed: Some(8),
+        gravity: Vec2::ZERO,
+        drag_coefficient: 0.0,
+        record_events: true,
+        ..ParticleConfig::default()
+    });
+
+    // Particles fly right through a zone into a wall and die on the way back
+    let mut emitter = Emitter::new(Vec2::ZERO);
+    emitter.settings.rate = 10.0;
+    emitter.settings.spread = 0.0;
+    emitter.settings.initial_velocity = 100.0;
+    emitter.settings.particle_lifetime = 3.0;
+    system.add_emitter(emitter);
+    system
+        .zones
+        .push(rectangle(Vec2::new(50.0, -10.0), Vec2::new(100.0, 10.0)));
+    system
+        .colliders
+        .push(rectangle(Vec2::new(200.0, -10.0), Vec2::new(260.0, 10.0)));
+
+    let (mut spawned, mut died, mut collided, mut entered, mut left) = (0, 0, 0, 0, 0);
+    for _ in 0..240 {
+        system.update(DT, &[]);
+        for event in system.drain_events() {
+            match event {
+                ParticleEvent::Spawned { .. } => spawned += 1,
+                ParticleEvent::Died { .. } => died += 1,
+                ParticleEvent::Collided { .. } => collided += 1,
+                ParticleEvent::Entered { .. } => entered += 1,
+                ParticleEvent::Left { .. } => left += 1,
+            }
+        }
+    }
+
+    println!("Spawned:  {}", spawned);
+    println!("Died:     {}", died);
+    println!("Collided: {}", collided);
+    println!("Entered:  {} / left: {}", entered, left);
+}
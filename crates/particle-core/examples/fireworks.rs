@@ -0,0 +1,179 @@
+//! Exercises bursts, rate envelopes and sub-emitters
+//! Run with: cargo run --example fireworks
+
+use glam::Vec2;
+use particle_core::{
+    Burst, Curve, Emitter, Outline, ParticleConfig, ParticleSystem, RateEnvelope, SubEmitter,
+    SubEmitterTrigger,
+};
+
+const DT: f32 = 1.0 / 60.0;
+
+fn main() {
+    println!("🎆 Testing burst emission...\n");
+
+    let config = ParticleConfig {
+        seed: Some(7),
+        particle_lifetime: 100.0,
+        ..ParticleConfig::default()
+    };
+
+    // One-shot burst from a disabled emitter, e.g. on click
+    let mut system = ParticleSystem::with_config(config.clone());
+    let mut emitter = Emitter::new(Vec2::new(640.0, 360.0));
+    emitter.settings.enabled = false;
+    emitter.settings.spread = std::f32::consts::PI;
+    let id = system.add_emitter(emitter).unwrap();
+
+    let spawned = system.burst(id, 500).unwrap();
+    println!("One-shot burst:   {} particles", spawned);
+    assert_eq!(spawned, 500);
+    assert_eq!(system.particle_count(), 500);
+
+    // Bursts stop at the particle budget
+    let spawned = system.burst(id, config.max_particles as u32).unwrap();
+    assert_eq!(system.particle_count(), config.max_particles);
+    assert_eq!(spawned, config.max_particles - 500);
+
+    // Pulses: 100 particles every half second, four times
+    let mut system = ParticleSystem::with_config(config.clone());
+    let mut emitter = Emitter::new(Vec2::ZERO);
+    emitter.settings.rate = 0.0;
+    emitter.settings.particle_lifetime = 100.0;
+    emitter
+        .settings
+        .bursts
+        .push(Burst::new(0.0, 100).repeating(0.5, Some(4)));
+    system.add_emitter(emitter);
+
+    let mut counts = Vec::new();
+    for _ in 0..180 {
+        system.update(DT, &[]);
+        counts.push(system.particle_count());
+    }
+    println!(
+        "Scheduled bursts: {} particles after 3s",
+        system.particle_count()
+    );
+    assert_eq!(counts[0], 100, "first burst fires on the first step");
+    assert_eq!(counts[25], 100, "no burst before the interval has passed");
+    assert_eq!(system.particle_count(), 400, "four cycles in total");
+
+    // Swell: the rate ramps from zero to full over two seconds
+    let mut system = ParticleSystem::with_config(config.clone());
+    let mut emitter = Emitter::new(Vec2::ZERO);
+    emitter.settings.rate = 600.0;
+    emitter.settings.particle_lifetime = 100.0;
+    emitter.settings.rate_envelope = Some(RateEnvelope::new(Curve::linear(0.0, 1.0), 2.0));
+    system.add_emitter(emitter);
+
+    for _ in 0..60 {
+        system.update(DT, &[]);
+    }
+    let first_second = system.particle_count();
+    for _ in 0..60 {
+        system.update(DT, &[]);
+    }
+    let second_second = system.particle_count() - first_second;
+    println!(
+        "Rate envelope:    {} then {} particles per second",
+        first_second, second_second
+    );
+
+    // Integral of the ramp: 150 in the first second, 450 in the second
+    assert!((140..=160).contains(&first_second));
+    assert!((440..=460).contains(&second_second));
+
+    // Rockets explode into sparks when they die
+    let mut system = ParticleSystem::with_config(ParticleConfig {
+        gravity: Vec2::ZERO,
+        ..config.clone()
+    });
+    let mut rocket = Emitter::new(Vec2::ZERO);
+    rocket.settings.enabled = false;
+    rocket.settings.spread = 0.0;
+    rocket.settings.particle_lifetime = 0.5;
+    rocket
+        .settings
+        .sub_emitters
+        .push(SubEmitter::new(SubEmitterTrigger::Death, 1, 50));
+    let mut sparks = Emitter::new(Vec2::ZERO);
+    sparks.settings.enabled = false;
+    sparks.settings.particle_lifetime = 1.0;
+    let rocket = system.add_emitter(rocket).unwrap();
+    system.add_emitter(sparks);
+
+    system.burst(rocket, 1);
+    let rocket_velocity = system.particles.velocities()[0];
+    for _ in 0..31 {
+        system.update(DT, &[]);
+    }
+    println!("Death sub-emitter: {} sparks", system.particle_count());
+    assert_eq!(system.particle_count(), 50);
+    assert!(system.particles.depths().iter().all(|&depth| depth == 1));
+    assert!(system
+        .particles
+        .emitters()
+        .iter()
+        .all(|&emitter| emitter == 1));
+
+    // Sparks inherit the rocket's velocity on top of their own
+    let mean = system.particles.velocities().iter().sum::<Vec2>() / 50.0;
+    assert!(mean.x > 0.5 * rocket_velocity.x);
+
+    // An emitter spawning from itself on birth stops at the depth limit
+    let mut system = ParticleSystem::with_config(config.clone());
+    let mut fractal = Emitter::new(Vec2::ZERO);
+    fractal.settings.enabled = false;
+    fractal
+        .settings
+        .sub_emitters
+        .push(SubEmitter::new(SubEmitterTrigger::Birth, 0, 2));
+    let fractal = system.add_emitter(fractal).unwrap();
+
+    system.burst(fractal, 1);
+    let expected: usize = (0..=config.max_sub_emitter_depth)
+        .map(|depth| 1 << depth)
+        .sum();
+    println!("Birth recursion:   {} particles", system.particle_count());
+    assert_eq!(system.particle_count(), expected);
+
+    // Drops splash when they hit an outline
+    let mut system = ParticleSystem::with_config(ParticleConfig {
+        gravity: Vec2::ZERO,
+        ..config
+    });
+    system.colliders.push(Outline::from_points(vec![
+        Vec2::new(50.0, -50.0),
+        Vec2::new(150.0, -50.0),
+        Vec2::new(150.0, 50.0),
+        Vec2::new(50.0, 50.0),
+    ]));
+    let mut drop = Emitter::new(Vec2::ZERO);
+    drop.settings.enabled = false;
+    drop.settings.spread = 0.0;
+    drop.settings.initial_velocity = 100.0;
+    drop.settings
+        .sub_emitters
+        .push(SubEmitter::new(SubEmitterTrigger::Collision, 1, 10));
+    let mut splash = Emitter::new(Vec2::ZERO);
+    splash.settings.enabled = false;
+    let drop = system.add_emitter(drop).unwrap();
+    system.add_emitter(splash);
+
+    system.burst(drop, 1);
+    for _ in 0..60 {
+        system.update(DT, &[]);
+    }
+    let splashes = system
+        .particles
+        .emitters()
+        .iter()
+        .filter(|&&e| e == 1)
+        .count();
+    println!("Collision splash:  {} particles", splashes);
+    assert_eq!(splashes, 10, "the drop splashes once and bounces off");
+    assert!(system.particles.velocities()[0].x < 0.0);
+
+    println!("\n🎉 Burst emission behaves as expected!");
+}
@@ -0,0 +1,154 @@
+//! Prints how flocks align, flee predators and regroup
+//!
+//! The flocking rules are asserted by the tests in `flocking.rs`.
+//! Run with: cargo run --example flocking
+
+use glam::Vec2;
+use particle_core::{
+    Burst, Emitter, EmitterShape, Flock, Force, GroupMask, Outline, ParticleConfig, ParticleSystem,
+};
+
+const DT: f32 = 1.0 / 60.0;
+const STARLINGS: GroupMask = GroupMask::group(2);
+const CENTRE: Vec2 = Vec2::new(400.0, 300.0);
+
+/// Starlings and unflocked dust scattered around the centre
+fn sky(flocks: Vec<Flock>) -> ParticleSystem {
+    let mut system = ParticleSystem::with_config(ParticleConfig {
+        seed: Some(9),
+        gravity: Vec2::ZERO,
+        drag_coefficient: 0.0,
+        flocks,
+        ..ParticleConfig::default()
+    });
+    for groups in [STARLINGS, GroupMask::DEFAULT] {
+        let mut emitter = Emitter::new(CENTRE);
+        emitter.settings.rate = 0.0;
+        emitter.settings.shape = EmitterShape::Circle { radius: 120.0 };
+        emitter.settings.spread = std::f32::consts::TAU;
+        emitter.settings.initial_velocity = 80.0;
+        emitter.settings.particle_lifetime = 100.0;
+        emitter.settings.groups = groups;
+        emitter.settings.bursts.push(Burst::new(0.0, 150));
+        system.add_emitter(emitter);
+    }
+    system
+}
+
+fn starlings() -> Flock {
+    Flock {
+        mask: STARLINGS,
+        ..Flock::default()
+    }
+}
+
+fn run(system: &mut ParticleSystem, seconds: f32, forces: &[Force]) {
+    for _ in 0..(seconds / DT).round() as u32 {
+        system.update(DT, forces);
+    }
+}
+
+/// Particles of `groups` as (position, velocity) pairs
+fn members(system: &ParticleSystem, groups: GroupMask) -> Vec<(Vec2, Vec2)> {
+    system
+        .particles
+        .iter()
+        .filter(|particle| groups.intersects(GroupMask(particle.groups)))
+        .map(|particle| (particle.pos(), particle.vel()))
+        .collect()
+}
+
+/// Mean cosine between each member's heading and that of its neighbours
+fn local_alignment(system: &ParticleSystem, groups: GroupMask) -> f32 {
+    let members = members(system, groups);
+    let mut total = 0.0;
+    for &(position, velocity) in &members {
+        let heading: Vec2 = members
+            .iter()
+            .filter(|(other, _)| *other != position && other.distance(position) < 40.0)
+            .map(|(_, velocity)| velocity.normalize_or_zero())
+            .sum();
+        total += velocity
+            .normalize_or_zero()
+            .dot(heading.normalize_or_zero());
+    }
+    total / members.len() as f32
+}
+
+/// Members of `groups` within `radius` of `point`
+fn near(system: &ParticleSystem, groups: GroupMask, point: Vec2, radius: f32) -> usize {
+    members(system, groups)
+        .iter()
+        .filter(|(position, _)| position.distance(point) < radius)
+        .count()
+}
+
+/// Square visitor silhouette around a point
+fn visitor(centre: Vec2) -> Outline {
+    Outline::from_points(vec![
+        centre + Vec2::new(-30.0, -60.0),
+        centre + Vec2::new(30.0, -60.0),
+        centre + Vec2::new(30.0, 60.0),
+        centre + Vec2::new(-30.0, 60.0),
+    ])
+}
+
+/// Centroid of the members of `groups`
+fn centroid(system: &ParticleSystem, groups: GroupMask) -> Vec2 {
+    let members = members(system, groups);
+    members.iter().map(|(position, _)| *position).sum::<Vec2>() / members.len() as f32
+}
+
+fn main() {
+    println!("🐦 Flocking\n");
+
+    // Starlings scattered every way line up with their neighbours
+    let mut system = sky(vec![starlings()]);
+    run(&mut system, DT, &[]);
+    let scattered = local_alignment(&system, STARLINGS);
+    run(&mut system, 5.0, &[]);
+    let aligned = local_alignment(&system, STARLINGS);
+    println!(
+        "Alignment: {:.2} at first / {:.2} flocked",
+        scattered, aligned
+    );
+
+    // A repulsor scares the flock away, even one too weak to push
+    let predator = centroid(&system, STARLINGS);
+    let scare = [Force::Repulsor {
+        position: predator,
+        strength: 0.0,
+        radius: 100.0,
+    }];
+    let mut brave = sky(vec![Flock {
+        avoidance: 0.0,
+        ..starlings()
+    }]);
+    run(&mut brave, DT, &[]);
+    run(&mut brave, 5.0, &[]);
+    run(&mut brave, 2.0, &scare);
+    run(&mut system, 2.0, &scare);
+    let (fled, stayed) = (
+        near(&system, STARLINGS, predator, 100.0),
+        near(&brave, STARLINGS, predator, 100.0),
+    );
+    println!("Repulsor:  {} near / {} without avoidance", fled, stayed);
+
+    // So does a visitor's silhouette
+    let visitor_at = centroid(&system, STARLINGS);
+    system.colliders.push(visitor(visitor_at));
+    brave.colliders.push(visitor(visitor_at));
+    run(&mut system, 1.0, &[]);
+    run(&mut brave, 1.0, &[]);
+    let (fled, stayed) = (
+        near(&system, STARLINGS, visitor_at, 80.0),
+        near(&brave, STARLINGS, visitor_at, 80.0),
+    );
+    println!("Outline:   {} near / {} without avoidance", fled, stayed);
+
+    // Once the visitor leaves the flock regroups
+    system.colliders.clear();
+    run(&mut system, 4.0, &[]);
+    let regrouped = local_alignment(&system, STARLINGS);
+    println!("Regrouped: {:.2}", regrouped);
+}
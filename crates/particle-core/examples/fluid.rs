@@ -0,0 +1,165 @@
+//! Prints how fluid groups pour around an outline and settle as a liquid
+//!
+//! The liquid behaviour is asserted by the tests in `fluid.rs`.
+//! Run with: cargo run --example fluid
+
+use glam::Vec2;
+use particle_core::{
+    EdgeMode, Emitter, Fluid, GroupMask, Outline, Particle, ParticleConfig, ParticleSystem,
+    WorldBounds, AABB,
+};
+
+const DT: f32 = 1.0 / 60.0;
+const WATER: GroupMask = GroupMask::group(1);
+
+/// Axis-aligned box as an outline
+fn rectangle(min: Vec2, max: Vec2) -> Outline {
+    Outline::from_points(vec![
+        min,
+        Vec2::new(max.x, min.y),
+        max,
+        Vec2::new(min.x, max.y),
+    ])
+}
+
+/// Torso of a person standing in the middle of the tank
+fn person() -> Outline {
+    rectangle(Vec2::new(170.0, 180.0), Vec2::new(230.0, 260.0))
+}
+
+/// Stream poured onto the person's head
+fn pour(x: f32, groups: GroupMask) -> Emitter {
+    let mut emitter = Emitter::new(Vec2::new(x, 40.0));
+    emitter.settings.rate = 200.0;
+    emitter.settings.spread = 0.3;
+    emitter.settings.direction = std::f32::consts::FRAC_PI_2;
+    emitter.settings.initial_velocity = 60.0;
+    emitter.settings.particle_lifetime = 100.0;
+    emitter.settings.groups = groups;
+    emitter
+}
+
+/// Tank of water and sand poured side by side; only water is fluid
+fn run() -> ParticleSystem {
+    let mut system = ParticleSystem::with_config(ParticleConfig {
+        seed: Some(12),
+        gravity: Vec2::new(0.0, 200.0),
+        drag_coefficient: 0.0,
+        bounds: Some(WorldBounds::new(
+            AABB::new(Vec2::ZERO, Vec2::new(400.0, 300.0)),
+            EdgeMode::Bounce {
+                restitution: 0.1,
+                friction: 0.1,
+            },
+        )),
+        fluid: Fluid {
+            mask: WATER,
+            ..Fluid::default()
+        },
+        ..ParticleConfig::default()
+    });
+    system.colliders.push(person());
+    system.add_emitter(pour(200.0, WATER));
+    system.add_emitter(pour(205.0, GroupMask::DEFAULT));
+
+    for step in 0..360 {
+        if step == 120 {
+            for emitter in &mut system.emitters {
+                emitter.settings.enabled = false;
+            }
+        }
+        system.update(DT, &[]);
+    }
+    system
+}
+
+/// Width of a sparse, weightless square of water after a second
+fn drop_width(surface_tension: f32) -> f32 {
+    let mut system = ParticleSystem::with_config(ParticleConfig {
+        gravity: Vec2::ZERO,
+        drag_coefficient: 0.0,
+        fluid: Fluid {
+            mask: WATER,
+            surface_tension,
+            ..Fluid::default()
+        },
+        ..ParticleConfig::default()
+    });
+    for i in 0..25 {
+        let position = Vec2::new((i % 5) as f32, (i / 5) as f32) * 12.0;
+        let mut particle = Particle::new(position, Vec2::ZERO, 100.0, 3.0, [1.0; 4]);
+        particle.groups = WATER.bits();
+        system.particles.push(particle);
+    }
+    for _ in 0..60 {
+        system.update(DT, &[]);
+    }
+
+    let xs = system.particles.positions().iter().map(|p| p.x);
+    xs.clone().fold(f32::MIN, f32::max) - xs.fold(f32::MAX, f32::min)
+}
+
+/// Positions of the particles in `groups`
+fn positions(system: &ParticleSystem, groups: GroupMask) -> Vec<Vec2> {
+    system
+        .particles
+        .iter()
+        .filter(|particle| groups.intersects(GroupMask(particle.groups)))
+        .map(|particle| particle.pos())
+        .collect()
+}
+
+/// Mean distance from each point to its nearest neighbour
+fn mean_spacing(points: &[Vec2]) -> f32 {
+    let total: f32 = points
+        .iter()
+        .enumerate()
+        .map(|(i, a)| {
+            points
+                .iter()
+                .enumerate()
+                .filter(|&(j, _)| j != i)
+                .map(|(_, b)| a.distance(*b))
+                .fold(f32::INFINITY, f32::min)
+        })
+        .sum();
+    total / points.len() as f32
+}
+
+fn main() {
+    println!("💧 Fluid particles\n");
+
+    let system = run();
+    let water = positions(&system, WATER);
+    let sand = positions(&system, GroupMask::DEFAULT);
+    println!("Water:     {} particles", water.len());
+
+    // Water pours off both shoulders
+    let left = water.iter().filter(|p| p.x < 170.0).count();
+    let right = water.iter().filter(|p| p.x > 230.0).count();
+    println!("Sides:     {} left / {} right", left, right);
+
+    // Pressure keeps the water spread out while the sand piles up
+    let (water_spacing, sand_spacing) = (mean_spacing(&water), mean_spacing(&sand));
+    println!(
+        "Spacing:   {:.2} water / {:.2} sand",
+        water_spacing, sand_spacing
+    );
+
+    // Viscosity calms the surface
+    let speed = system
+        .particles
+        .iter()
+        .filter(|particle| WATER.intersects(GroupMask(particle.groups)))
+        .map(|particle| particle.vel().length())
+        .sum::<f32>()
+        / water.len() as f32;
+    println!("Speed:     {:.1} average", speed);
+
+    // Surface tension holds a splash together
+    let (loose, held) = (drop_width(0.0), drop_width(2000.0));
+    println!(
+        "Drop:      {:.1} wide loose / {:.1} with tension",
+        loose, held
+    );
+}
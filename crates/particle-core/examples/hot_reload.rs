@@ -0,0 +1,94 @@
+//! Prints how edits to a watched scene file are applied in place
+//!
+//! Reloading and error reporting are asserted by the tests in
+//! `hot_reload.rs`.
+//! Run with: cargo run --features hot-reload --example hot_reload
+
+use particle_core::{ParticleSystem, SceneWatcher};
+use std::fs::{self, File};
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+const DT: f32 = 1.0 / 60.0;
+
+/// Scene with a fountain of the given rate and optional extra emitter and force
+fn scene(rate: f32, extras: bool) -> String {
+    let (sparkles, attractor) = if extras {
+        (
+            r#", { "position": [600.0, 300.0], "rate": 50.0, "particle_lifetime": 10.0 }"#,
+            r#", { "Attractor": { "position": [400.0, 300.0], "strength": 50000.0, "radius": 200.0 } }"#,
+        )
+    } else {
+        ("", "")
+    };
+    format!(
+        r#"{{
+  "version": 1,
+  "config": {{ "seed": 3 }},
+  "emitters": [
+    {{ "position": [200.0, 500.0], "rate": {}, "particle_lifetime": 10.0 }}{}
+  ],
+  "forces": [
+    {{ "Wind": {{ "direction": [1.0, 0.0], "strength": 10.0, "turbulence": 0.0 }} }}{}
+  ]
+}}"#,
+        rate, sparkles, attractor
+    )
+}
+
+/// Saves the scene as an editor would, with a fresh modification time
+fn save(path: &Path, contents: &str, saves: &mut u64) {
+    fs::write(path, contents).unwrap();
+    *saves += 1;
+    let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000 + *saves);
+    File::options()
+        .write(true)
+        .open(path)
+        .unwrap()
+        .set_modified(modified)
+        .unwrap();
+}
+
+fn main() {
+    println!("🔥 Scene hot-reloading\n");
+
+    let path = std::env::temp_dir().join(format!("hot-reload-{}.json", std::process::id()));
+    let mut saves = 0;
+    save(&path, &scene(100.0, false), &mut saves);
+
+    // The first poll loads the scene into the fresh system
+    let mut watcher = SceneWatcher::new(&path);
+    let mut system = ParticleSystem::new();
+    let mut forces = Vec::new();
+    for _ in 0..60 {
+        watcher.reload(&mut system, &mut forces);
+        system.update(DT, &forces);
+    }
+    println!("Loaded:    {} particles", system.particle_count());
+
+    // Edits update the running emitters and keep every particle
+    save(&path, &scene(300.0, true), &mut saves);
+    for _ in 0..60 {
+        watcher.reload(&mut system, &mut forces);
+        system.update(DT, &forces);
+    }
+    println!(
+        "Edited:    {} particles from {} emitters",
+        system.particle_count(),
+        system.emitters.len()
+    );
+
+    // A broken save is reported and the last good scene keeps running
+    let broken = scene(500.0, true).replace("\"rate\": 500", "\"rate\": 500,,");
+    save(&path, &broken, &mut saves);
+    if let Some(Err(error)) = watcher.reload(&mut system, &mut forces) {
+        println!("Rejected:  {}", error);
+    }
+
+    // Removing an emitter takes only its particles with it
+    save(&path, &scene(300.0, false), &mut saves);
+    watcher.reload(&mut system, &mut forces);
+    println!("Trimmed:   {} particles", system.particle_count());
+
+    fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,89 @@
+//! Checks that force and collider masks only act on their particle groups
+//! Run with: cargo run --example layers
+
+use glam::Vec2;
+use particle_core::{Emitter, Force, GroupMask, Outline, ParticleConfig, ParticleSystem};
+
+const DT: f32 = 1.0 / 60.0;
+const BACKGROUND: GroupMask = GroupMask::group(0);
+const FOREGROUND: GroupMask = GroupMask::group(1);
+
+/// Emitter dropping still particles on a line across the scene
+fn layer(y: f32, groups: GroupMask) -> Emitter {
+    let mut emitter = Emitter::new(Vec2::new(0.0, y));
+    emitter.settings.initial_velocity = 0.0;
+    emitter.settings.spread = 0.0;
+    emitter.settings.rate = 0.0;
+    emitter.settings.particle_lifetime = 10.0;
+    emitter.settings.groups = groups;
+    emitter
+}
+
+fn main() {
+    println!("🧅 Testing particle groups...\n");
+
+    let mut system = ParticleSystem::with_config(ParticleConfig {
+        seed: Some(2),
+        gravity: Vec2::ZERO,
+        drag_coefficient: 0.0,
+        ..ParticleConfig::default()
+    });
+    let background = system.add_emitter(layer(0.0, BACKGROUND)).unwrap();
+    let foreground = system.add_emitter(layer(0.0, FOREGROUND)).unwrap();
+    system.burst(background, 50);
+    system.burst(foreground, 50);
+
+    // Wind for everything, a push only for the foreground
+    let forces = [
+        Force::wind(Vec2::new(0.0, 1.0), 10.0),
+        Force::wind(Vec2::new(1.0, 0.0), 100.0).masked(FOREGROUND),
+    ];
+    for _ in 0..60 {
+        system.update(DT, &forces);
+    }
+
+    let mean_x = |groups: GroupMask| {
+        let xs: Vec<f32> = system
+            .particles
+            .positions()
+            .iter()
+            .zip(system.particles.groups())
+            .filter(|(_, &g)| GroupMask(g) == groups)
+            .map(|(p, _)| p.x)
+            .collect();
+        xs.iter().sum::<f32>() / xs.len() as f32
+    };
+    println!("Background x: {:.1}", mean_x(BACKGROUND));
+    println!("Foreground x: {:.1}", mean_x(FOREGROUND));
+    assert!(mean_x(BACKGROUND).abs() < 1e-3);
+    assert!(mean_x(FOREGROUND) > 40.0);
+    assert!(system.particles.positions().iter().all(|p| p.y > 4.0));
+
+    // Colliders on one layer let the other pass through
+    let mut wall = Outline::from_points(vec![
+        Vec2::new(-10.0, -10.0),
+        Vec2::new(10.0, -10.0),
+        Vec2::new(10.0, 10.0),
+        Vec2::new(-10.0, 10.0),
+    ]);
+    wall.mask = FOREGROUND;
+    system.colliders.push(wall);
+    system.particles.clear();
+    system.burst(background, 20);
+    system.burst(foreground, 20);
+    system.update(DT, &[]);
+
+    let inside = system
+        .particles
+        .positions()
+        .iter()
+        .zip(system.particles.groups())
+        .filter(|(p, _)| p.x.abs() < 9.9 && p.y.abs() < 9.9)
+        .map(|(_, &g)| GroupMask(g))
+        .collect::<Vec<_>>();
+    println!("Inside the wall: {} particles", inside.len());
+    assert_eq!(inside.len(), 20);
+    assert!(inside.iter().all(|&g| g == BACKGROUND));
+
+    println!("\n🎉 Groups keep their layers apart!");
+}
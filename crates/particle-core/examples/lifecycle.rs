@@ -0,0 +1,75 @@
+//! Prints emitter durations, looping, start delays, prewarm and removal
+//!
+//! The lifecycle rules are asserted by the tests in `particles.rs`.
+//! Run with: cargo run --example lifecycle
+
+use glam::Vec2;
+use particle_core::{Burst, Emitter, ParticleConfig, ParticleSystem};
+
+const DT: f32 = 1.0 / 60.0;
+
+fn emitter(rate: f32, lifetime: f32) -> Emitter {
+    let mut emitter = Emitter::new(Vec2::ZERO);
+    emitter.settings.rate = rate;
+    emitter.settings.particle_lifetime = lifetime;
+    emitter
+}
+
+fn main() {
+    println!("⏳ Emitter lifecycles\n");
+
+    // A delayed, one second emitter spawns its rate once, then goes away
+    let mut system = ParticleSystem::with_config(ParticleConfig {
+        seed: Some(5),
+        ..ParticleConfig::default()
+    });
+    let mut timed = emitter(120.0, 2.0);
+    timed.settings.start_delay = 0.25;
+    timed.settings.duration = Some(1.0);
+    let id = system.add_emitter(timed).unwrap();
+
+    while !system.emitter(id).unwrap().is_finished() {
+        system.update(DT, &[]);
+    }
+    println!(
+        "Timed emitter:   {} particles over its duration",
+        system.particle_count()
+    );
+
+    let mut frames = 0;
+    while system.emitter(id).is_some() {
+        system.update(DT, &[]);
+        frames += 1;
+    }
+    println!("Removed:         {} frames after finishing", frames);
+
+    // Looping emitters fire their bursts every cycle
+    let mut looping = emitter(0.0, 10.0);
+    looping.settings.duration = Some(0.5);
+    looping.settings.looping = true;
+    looping.settings.bursts.push(Burst::new(0.0, 10));
+    system.add_emitter(looping);
+    for _ in 0..110 {
+        system.update(DT, &[]);
+    }
+    println!(
+        "Looping bursts:  {} particles in 4 cycles",
+        system.particle_count()
+    );
+
+    // Prewarm starts a scene in its steady state
+    let mut cold = ParticleSystem::new();
+    let mut warm = ParticleSystem::new();
+    for system in [&mut cold, &mut warm] {
+        system.reseed(8);
+        system.add_emitter(emitter(100.0, 2.0));
+    }
+    warm.prewarm(3.0, &[]);
+    cold.update(DT, &[]);
+    warm.update(DT, &[]);
+    println!(
+        "Prewarm:         {} particles cold, {} warm",
+        cold.particle_count(),
+        warm.particle_count()
+    );
+}
@@ -17,12 +17,12 @@ fn main() {
     system.add_emitter(emitter);
     println!("✓ Added emitter at (100, 100)");
 
-    // Create forces
+    // Gravity comes from the config, other forces are passed per update
+    system.config.gravity = Vec2::new(0.0, 98.0);
     let forces = vec![
-        Force::gravity(0.0, 98.0),  // Gravity
         Force::wind(Vec2::new(10.0, 0.0), 5.0),  // Light wind
     ];
-    println!("✓ Created forces (gravity + wind)");
+    println!("✓ Created forces (config gravity + wind)");
 
     // Simulate for a few frames
     println!("\n📊 Simulating 120 frames (2 seconds at 60fps)...\n");
@@ -0,0 +1,63 @@
+//! Prints the orbit stability of the available integrators
+//!
+//! The drift bounds are asserted by the tests in `physics.rs`.
+//! Run with: cargo run --example orbit_stability
+
+use glam::Vec2;
+use particle_core::{Force, Integrator};
+
+const STRENGTH: f32 = 200_000.0;
+const ORBIT_RADIUS: f32 = 100.0;
+const ORBITS: f32 = 20.0;
+const DT: f32 = 1.0 / 30.0;
+
+/// Simulates a single particle on a circular orbit around an attractor and
+/// returns the largest relative deviation from the starting radius
+fn radial_drift(integrator: Integrator) -> f32 {
+    let attractor = Force::attractor(Vec2::ZERO, STRENGTH, 10.0 * ORBIT_RADIUS);
+
+    // Circular orbit speed: v^2 / r = a(r)
+    let mut pos = Vec2::new(ORBIT_RADIUS, 0.0);
+    let acceleration = attractor.calculate_at(pos).length();
+    let speed = (ORBIT_RADIUS * acceleration).sqrt();
+    let mut vel = Vec2::new(0.0, speed);
+
+    let period = std::f32::consts::TAU * ORBIT_RADIUS / speed;
+    let steps = (ORBITS * period / DT) as usize;
+
+    let mut max_drift: f32 = 0.0;
+    for _ in 0..steps {
+        (pos, vel) = integrator.step(pos, vel, DT, |p| attractor.calculate_at(p));
+        max_drift = max_drift.max((pos.length() / ORBIT_RADIUS - 1.0).abs());
+    }
+
+    max_drift
+}
+
+fn main() {
+    println!("🪐 Orbit stability over {} orbits at dt = 1/30\n", ORBITS);
+
+    let explicit = radial_drift(Integrator::ExplicitEuler);
+    let semi_implicit = radial_drift(Integrator::SemiImplicitEuler);
+    let verlet = radial_drift(Integrator::VelocityVerlet);
+    let rk4 = radial_drift(Integrator::Rk4);
+
+    println!(
+        "Explicit Euler:      {:>8.4}% max radial drift",
+        explicit * 100.0
+    );
+    println!(
+        "Semi-implicit Euler: {:>8.4}% max radial drift",
+        semi_implicit * 100.0
+    );
+    println!(
+        "Velocity Verlet:     {:>8.4}% max radial drift",
+        verlet * 100.0
+    );
+    println!(
+        "RK4:                 {:>8.4}% max radial drift",
+        rk4 * 100.0
+    );
+
+    println!("\n🎉 Orbit comparison complete!");
+}
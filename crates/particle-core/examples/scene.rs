@@ -0,0 +1,62 @@
+//! Prints loading, exporting and error reporting of JSON scenes
+//!
+//! Defaults, round trips and error positions are asserted by the tests in
+//! `scene.rs`.
+//! Run with: cargo run --features scene --example scene
+
+use particle_core::{ParticleSystem, Scene};
+
+const DT: f32 = 1.0 / 60.0;
+
+/// Scene the web app starts with
+const DEFAULT_SCENE: &str = include_str!("../../../scenes/default.json");
+
+/// Fountain whose drops burst into sparks, pulled towards the middle
+const FOUNTAIN: &str = include_str!("../../../scenes/fountain.json");
+
+/// Runs a scene for two seconds and returns the resulting system
+fn play(scene: &Scene) -> ParticleSystem {
+    let (mut system, forces) = scene.build();
+    for _ in 0..120 {
+        system.update(DT, &forces);
+    }
+    system
+}
+
+/// Prints why a scene could not be loaded
+fn reject(source: &str) {
+    if let Err(error) = Scene::from_json(source) {
+        println!("Rejected:  {}", error);
+    }
+}
+
+fn main() {
+    println!("🎬 Scene files\n");
+
+    let (system, forces) = Scene::from_json(DEFAULT_SCENE).unwrap().build();
+    println!(
+        "Default:   {} emitter, {} forces",
+        system.emitters.len(),
+        forces.len()
+    );
+
+    let scene = Scene::from_json(FOUNTAIN).unwrap();
+    let original = play(&scene);
+    println!("Fountain:  {} particles", original.particle_count());
+
+    // Exported scenes load back to the same look
+    let (system, forces) = scene.build();
+    let exported = system.to_scene(&forces).to_json();
+    let reloaded = play(&Scene::from_json(&exported).unwrap());
+    println!(
+        "Exported:  {} bytes, {} particles on reload",
+        exported.len(),
+        reloaded.particle_count()
+    );
+
+    // Errors point at the offending spot
+    reject(&FOUNTAIN.replace("\"rate\": 150.0", "\"rat\": 150.0"));
+    reject(&FOUNTAIN.replace("[400.0, 600.0]", "[400.0 600.0]"));
+    reject(&FOUNTAIN.replace("\"version\": 1", "\"version\": 2"));
+    reject(&FOUNTAIN.replace("\"emitter\": 0", "\"emitter\": 7"));
+}
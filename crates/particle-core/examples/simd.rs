@@ -0,0 +1,116 @@
+//! Benchmarks batched force evaluation and integration against the per-particle path
+//! Run with: cargo run --release --example simd
+
+use glam::Vec2;
+use particle_core::{Force, Integrator, IntegratorScratch, ParticleRng};
+use rand::{Rng, SeedableRng};
+use std::time::{Duration, Instant};
+
+const PARTICLES: usize = 100_000;
+const RUNS: u32 = 50;
+const DT: f32 = 1.0 / 60.0;
+
+/// Returns the average time of `RUNS` calls of `f`
+fn time(mut f: impl FnMut()) -> Duration {
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        f();
+    }
+    start.elapsed() / RUNS
+}
+
+fn report(name: &str, scalar: Duration, batched: Duration) {
+    println!(
+        "{:<22} {:>9.2?} -> {:>9.2?}  ({:.1}x)",
+        name,
+        scalar,
+        batched,
+        scalar.as_secs_f64() / batched.as_secs_f64()
+    );
+}
+
+fn main() {
+    println!(
+        "🏎️  Benchmarking batched particle math at {} particles...\n",
+        PARTICLES
+    );
+
+    let mut rng = ParticleRng::seed_from_u64(4);
+    let positions: Vec<Vec2> = (0..PARTICLES)
+        .map(|_| Vec2::new(rng.gen_range(0.0..1280.0), rng.gen_range(0.0..720.0)))
+        .collect();
+    let velocities: Vec<Vec2> = (0..PARTICLES)
+        .map(|_| Vec2::new(rng.gen_range(-50.0..50.0), rng.gen_range(-50.0..50.0)))
+        .collect();
+
+    let forces = [
+        Force::attractor(Vec2::new(640.0, 360.0), 5.0e5, 600.0),
+        Force::repulsor(Vec2::new(300.0, 200.0), 2.0e5, 300.0),
+        Force::gravity(0.0, 100.0),
+    ];
+
+    // Forces: one `calculate_at` per particle against `accumulate_all`
+    let field = |pos: Vec2| {
+        forces
+            .iter()
+            .map(|force| force.calculate_at(pos))
+            .sum::<Vec2>()
+    };
+    let mut scalar = vec![Vec2::ZERO; PARTICLES];
+    let mut batched = vec![Vec2::ZERO; PARTICLES];
+    let scalar_time = time(|| {
+        for (out, pos) in scalar.iter_mut().zip(&positions) {
+            *out = field(*pos);
+        }
+    });
+    let batched_time = time(|| {
+        batched.fill(Vec2::ZERO);
+        for force in &forces {
+            force.accumulate_all(&positions, &mut batched);
+        }
+    });
+    assert_eq!(scalar, batched, "batched forces differ from calculate_at");
+    report("Forces", scalar_time, batched_time);
+
+    // Integration: `step` per particle against `step_all` over the slices
+    let mut scratch = IntegratorScratch::default();
+    for integrator in [
+        Integrator::ExplicitEuler,
+        Integrator::SemiImplicitEuler,
+        Integrator::VelocityVerlet,
+        Integrator::Rk4,
+    ] {
+        let (mut scalar_pos, mut scalar_vel) = (positions.clone(), velocities.clone());
+        let scalar_time = time(|| {
+            for (pos, vel) in scalar_pos.iter_mut().zip(&mut scalar_vel) {
+                (*pos, *vel) = integrator.step(*pos, *vel, DT, field);
+            }
+        });
+
+        let (mut batched_pos, mut batched_vel) = (positions.clone(), velocities.clone());
+        let batched_time = time(|| {
+            integrator.step_all(
+                &mut batched_pos,
+                &mut batched_vel,
+                DT,
+                &mut scratch,
+                |positions, out| {
+                    out.fill(Vec2::ZERO);
+                    for force in &forces {
+                        force.accumulate_all(positions, out);
+                    }
+                },
+            );
+        });
+
+        assert_eq!(scalar_pos, batched_pos, "{:?} positions differ", integrator);
+        assert_eq!(
+            scalar_vel, batched_vel,
+            "{:?} velocities differ",
+            integrator
+        );
+        report(&format!("{:?}", integrator), scalar_time, batched_time);
+    }
+
+    println!("\n🎉 Batched results match the per-particle path!");
+}
@@ -0,0 +1,91 @@
+//! Prints a snapshot round trip and the continued simulation
+//!
+//! Exact continuation and rejection of damaged snapshots are asserted by
+//! the tests in `snapshot.rs`.
+//! Run with: cargo run --features serde --example snapshot
+
+use glam::Vec2;
+use particle_core::{
+    Burst, Emitter, Force, Gradient, Outline, ParticleConfig, ParticleSystem, Snapshot, SubEmitter,
+    SubEmitterTrigger,
+};
+
+const DT: f32 = 1.0 / 60.0;
+
+/// Builds a scene using emitter timers, sub-emitters, colliders and curves
+fn scene() -> (ParticleSystem, Vec<Force>) {
+    let mut system = ParticleSystem::with_config(ParticleConfig {
+        seed: Some(11),
+        max_particles: 2000,
+        ..ParticleConfig::default()
+    });
+
+    let mut sparks = Emitter::new(Vec2::ZERO);
+    sparks.settings.enabled = false;
+    sparks.settings.spread = std::f32::consts::PI;
+    sparks.settings.particle_lifetime = 0.5;
+
+    let mut fountain = Emitter::new(Vec2::new(400.0, 500.0));
+    fountain.settings.rate = 180.0;
+    fountain.settings.direction = -std::f32::consts::FRAC_PI_2;
+    fountain.settings.initial_velocity = 250.0;
+    fountain.settings.particle_lifetime = 2.0;
+    fountain.settings.duration = Some(1.5);
+    fountain.settings.looping = true;
+    fountain.settings.bursts = vec![Burst::new(0.25, 40)];
+    fountain.settings.color_over_life =
+        Some(Gradient::linear([1.0, 0.8, 0.2, 1.0], [0.8, 0.1, 0.1, 0.0]));
+    fountain.settings.sub_emitters = vec![SubEmitter::new(SubEmitterTrigger::Death, 0, 3)];
+
+    system.add_emitter(sparks);
+    system.add_emitter(fountain);
+    system.colliders.push(Outline::from_points(vec![
+        Vec2::new(300.0, 200.0),
+        Vec2::new(500.0, 200.0),
+        Vec2::new(500.0, 240.0),
+        Vec2::new(300.0, 240.0),
+    ]));
+
+    let forces = vec![
+        Force::wind(Vec2::new(1.0, 0.0), 30.0),
+        Force::attractor(Vec2::new(400.0, 300.0), 2.0e5, 300.0),
+    ];
+    (system, forces)
+}
+
+fn main() {
+    println!("💾 Snapshot and restore\n");
+
+    let (mut original, forces) = scene();
+    for _ in 0..90 {
+        original.update(DT, &forces);
+    }
+
+    // Round-trip through JSON as a crash-recovery file would
+    let json = serde_json::to_string(&original.snapshot(&forces)).unwrap();
+    let snapshot: Snapshot = serde_json::from_str(&json).unwrap();
+    println!(
+        "Snapshot:  {} particles, {} KiB",
+        original.particle_count(),
+        json.len() / 1024
+    );
+
+    let mut restored = ParticleSystem::new();
+    let restored_forces = restored.restore(snapshot.clone()).unwrap();
+    for _ in 0..120 {
+        original.update(DT, &forces);
+        restored.update(DT, &restored_forces);
+    }
+    println!(
+        "Continued: {} particles, {} in the original",
+        restored.particle_count(),
+        original.particle_count()
+    );
+
+    // Damaged snapshots are rejected and leave the system untouched
+    let mut orphaned = snapshot;
+    orphaned.emitters.truncate(1);
+    if let Err(error) = restored.restore(orphaned) {
+        println!("Rejected:  {}", error);
+    }
+}
@@ -0,0 +1,84 @@
+//! Checks the counts, motion and phase timings reported by each update
+//! Run with: cargo run --example stats
+
+use glam::Vec2;
+use particle_core::{Emitter, Force, OverflowPolicy, ParticleConfig, ParticleSystem};
+
+const DT: f32 = 1.0 / 60.0;
+
+fn main() {
+    println!("📊 Testing step statistics...\n");
+
+    let mut system = ParticleSystem::with_config(ParticleConfig {
+        max_particles: 500,
+        seed: Some(4),
+        overflow: OverflowPolicy::Reject,
+        ..ParticleConfig::default()
+    });
+
+    // A short-lived fountain and a flood that soon fills its share
+    let mut fountain = Emitter::new(Vec2::new(100.0, 100.0));
+    fountain.settings.rate = 120.0;
+    fountain.settings.particle_lifetime = 0.5;
+    system.add_emitter(fountain);
+    let mut flood = Emitter::new(Vec2::new(300.0, 100.0));
+    flood.settings.rate = 3000.0;
+    flood.settings.particle_lifetime = 60.0;
+    flood.settings.max_particles = Some(300);
+    system.add_emitter(flood);
+    let forces = [Force::Attractor {
+        position: Vec2::new(200.0, 200.0),
+        strength: 50000.0,
+        radius: 300.0,
+    }];
+
+    let (mut spawned, mut died, mut dropped) = (0, 0, 0);
+    for _ in 0..120 {
+        let before = system.particle_count();
+        system.update(DT, &forces);
+        let stats = system.stats();
+
+        // Every particle is accounted for
+        assert_eq!(stats.particles, system.particle_count());
+        assert_eq!(stats.particles, before + stats.spawned - stats.died);
+        assert_eq!(
+            stats.emitter_particles.iter().sum::<usize>(),
+            stats.particles
+        );
+        spawned += stats.spawned;
+        died += stats.died;
+        dropped += stats.dropped;
+    }
+    println!("Spawned:   {}", spawned);
+    println!("Died:      {}", died);
+    println!("Dropped:   {}", dropped);
+    assert_eq!(
+        dropped,
+        system.emitters.iter().map(|e| e.dropped()).sum::<u64>()
+    );
+    assert!(dropped > 1000 && died > 50);
+
+    // Motion matches the particles themselves
+    let stats = system.stats();
+    let (mut speed, mut energy) = (0.0, 0.0);
+    for particle in system.particles.iter() {
+        speed += particle.vel().length();
+        energy += 0.5 * particle.mass * particle.vel().length_squared();
+    }
+    speed /= system.particle_count() as f32;
+    println!("Speed:     {:.1} average", stats.average_speed);
+    println!("Energy:    {:.0}", stats.kinetic_energy);
+    assert!((stats.average_speed - speed).abs() < 1e-3 * speed);
+    assert!((stats.kinetic_energy - energy).abs() < 1e-3 * energy);
+
+    // The phases fit within the whole update
+    let timings = stats.timings;
+    println!("Timings:   {:?}", timings);
+    assert!(!timings.total.is_zero() && !timings.forces.is_zero());
+    assert!(
+        timings.forces + timings.integration + timings.collision + timings.emission
+            <= timings.total
+    );
+
+    println!("\n🎉 Each step reports what it did and how long it took!");
+}
@@ -0,0 +1,70 @@
+//! Checks that fast-moving emitters leave continuous trails
+//! Run with: cargo run --example trails
+
+use glam::Vec2;
+use particle_core::{Emitter, ParticleConfig, ParticleSystem};
+
+const DT: f32 = 1.0 / 60.0;
+const SPEED: f32 = 6000.0;
+
+fn main() {
+    println!("🌠 Testing swept emission...\n");
+
+    let mut system = ParticleSystem::with_config(ParticleConfig {
+        seed: Some(3),
+        gravity: Vec2::ZERO,
+        drag_coefficient: 0.0,
+        ..ParticleConfig::default()
+    });
+
+    // Straight down, no spread, half the emitter's motion inherited
+    let mut emitter = Emitter::new(Vec2::ZERO);
+    emitter.settings.rate = 600.0;
+    emitter.settings.spread = 0.0;
+    emitter.settings.initial_velocity = 10.0;
+    emitter.settings.direction = std::f32::consts::FRAC_PI_2;
+    emitter.settings.inherit_velocity = 0.5;
+    system.add_emitter(emitter);
+
+    // One frame at rest so the emitter knows where it started
+    system.update(DT, &[]);
+    system.particles.clear();
+
+    // The emitter jumps 100 pixels per frame, a fast hand swipe
+    for _ in 0..30 {
+        system.emitters[0].settings.position.x += SPEED * DT;
+        system.update(DT, &[]);
+    }
+
+    let mut xs: Vec<f32> = system.particles.positions().iter().map(|p| p.x).collect();
+    xs.sort_by(f32::total_cmp);
+    let largest_gap = xs.windows(2).map(|w| w[1] - w[0]).fold(0.0, f32::max);
+
+    println!("Particles:        {}", system.particle_count());
+    println!("Emitter velocity: {:?}", system.emitters[0].velocity());
+    println!("Largest gap:      {:.2} px", largest_gap);
+
+    // 10 spawns per 100 pixel frame, drifting back at half the emitter's speed
+    assert!(
+        largest_gap < 6.0,
+        "particles clumped with a {} px gap",
+        largest_gap
+    );
+    assert!(system.emitters[0]
+        .velocity()
+        .abs_diff_eq(Vec2::new(SPEED, 0.0), 0.01));
+
+    for velocity in system.particles.velocities() {
+        assert!((velocity.x - 0.5 * SPEED).abs() < 1.0);
+        assert!((velocity.y - 10.0).abs() < 1e-3);
+    }
+
+    // Teleporting does not sweep across the jump
+    system.particles.clear();
+    system.emitters[0].teleport(Vec2::new(-5000.0, 0.0));
+    system.update(DT, &[]);
+    assert!(system.particles.positions().iter().all(|p| p.x < -4000.0));
+    assert_eq!(system.emitters[0].velocity(), Vec2::ZERO);
+
+    println!("\n🎉 Trails are continuous!");
+}
@@ -0,0 +1,250 @@
+//! World bounds that keep particles within the simulated area
+
+use crate::collision::AABB;
+use crate::physics::Vec2;
+
+/// What happens to a particle that crosses an edge of the world bounds
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EdgeMode {
+    /// Reappear at the opposite edge, as on a torus
+    Wrap,
+    /// Reflect off the edge
+    Bounce {
+        /// Fraction of the speed into the edge kept after bouncing
+        restitution: f32,
+        /// Fraction of the speed along the edge lost per bounce
+        friction: f32,
+    },
+    /// Stop at the edge, keeping only the speed along it
+    Clamp,
+    /// Die at the edge
+    Kill,
+}
+
+impl EdgeMode {
+    /// Orders the modes for `WorldBounds::confine` when two edges are crossed
+    fn precedence(self) -> u8 {
+        match self {
+            Self::Wrap => 0,
+            Self::Clamp => 1,
+            Self::Bounce { .. } => 2,
+            Self::Kill => 3,
+        }
+    }
+}
+
+/// Area particles are kept in, with the behaviour at each of its edges
+///
+/// Edges are named as on screen, with y pointing down: `top` is the edge at
+/// `area.min.y` and `bottom` the one at `area.max.y`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WorldBounds {
+    pub area: AABB,
+    pub left: EdgeMode,
+    pub right: EdgeMode,
+    pub top: EdgeMode,
+    pub bottom: EdgeMode,
+}
+
+impl WorldBounds {
+    /// Creates bounds with the same mode on every edge
+    pub fn new(area: AABB, mode: EdgeMode) -> Self {
+        Self {
+            area,
+            left: mode,
+            right: mode,
+            top: mode,
+            bottom: mode,
+        }
+    }
+
+    /// Moves a particle that left the area back according to the edge modes
+    ///
+    /// Wrapping shifts `prev_position` along with the particle so it does
+    /// not streak across the area when interpolated. Particles crossing a
+    /// `Kill` edge are left where they are for the caller to remove.
+    ///
+    /// Returns the mode of the edge crossed, or `None` if the particle is
+    /// inside. Past a corner, `Kill` wins over `Bounce`, then `Clamp`.
+    pub fn confine(
+        &self,
+        position: &mut Vec2,
+        prev_position: &mut Vec2,
+        velocity: &mut Vec2,
+    ) -> Option<EdgeMode> {
+        let edges = [(self.left, self.right), (self.top, self.bottom)];
+        let mut crossed: Option<EdgeMode> = None;
+        for (axis, (low, high)) in edges.into_iter().enumerate() {
+            let (min, max) = (self.area.min[axis], self.area.max[axis]);
+            let (mode, edge, inward) = if position[axis] < min {
+                (low, min, 1.0)
+            } else if position[axis] > max {
+                (high, max, -1.0)
+            } else {
+                continue;
+            };
+
+            match mode {
+                EdgeMode::Wrap => {
+                    let size = max - min;
+                    let wrapped = if size > 0.0 {
+                        min + (position[axis] - min).rem_euclid(size)
+                    } else {
+                        edge
+                    };
+                    prev_position[axis] += wrapped - position[axis];
+                    position[axis] = wrapped;
+                }
+                EdgeMode::Bounce {
+                    restitution,
+                    friction,
+                } => {
+                    // Mirror the overshoot back inside, shortened like the speed
+                    let overshoot = (edge - position[axis]) * restitution;
+                    position[axis] = (edge + overshoot).clamp(min, max);
+                    if velocity[axis] * inward < 0.0 {
+                        velocity[axis] = -velocity[axis] * restitution;
+                        velocity[1 - axis] *= (1.0 - friction).clamp(0.0, 1.0);
+                    }
+                }
+                EdgeMode::Clamp => {
+                    position[axis] = edge;
+                    if velocity[axis] * inward < 0.0 {
+                        velocity[axis] = 0.0;
+                    }
+                }
+                EdgeMode::Kill => {}
+            }
+
+            if crossed.is_none_or(|other| mode.precedence() > other.precedence()) {
+                crossed = Some(mode);
+            }
+        }
+        crossed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::emission::{Burst, SubEmitter, SubEmitterTrigger};
+    use crate::particles::{Emitter, ParticleConfig, ParticleSystem};
+
+    const DT: f32 = 1.0 / 60.0;
+
+    /// Weightless system confined to a 100×100 box
+    fn boxed(left: EdgeMode, right: EdgeMode, top: EdgeMode, bottom: EdgeMode) -> ParticleSystem {
+        ParticleSystem::with_config(ParticleConfig {
+            seed: Some(2),
+            gravity: Vec2::ZERO,
+            drag_coefficient: 0.0,
+            bounds: Some(WorldBounds {
+                area: AABB::new(Vec2::ZERO, Vec2::splat(100.0)),
+                left,
+                right,
+                top,
+                bottom,
+            }),
+            ..ParticleConfig::default()
+        })
+    }
+
+    /// Emitter firing `count` particles at once with the given velocity
+    fn shot(position: Vec2, velocity: Vec2, count: u32) -> Emitter {
+        let mut emitter = Emitter::new(position);
+        emitter.settings.rate = 0.0;
+        emitter.settings.spread = 0.0;
+        emitter.settings.direction = velocity.y.atan2(velocity.x);
+        emitter.settings.initial_velocity = velocity.length();
+        emitter.settings.particle_lifetime = 10.0;
+        emitter.settings.bursts.push(Burst::new(0.0, count));
+        emitter
+    }
+
+    #[test]
+    fn wrapping_carries_particles_across_with_a_short_trail() {
+        let mut system = boxed(
+            EdgeMode::Wrap,
+            EdgeMode::Wrap,
+            EdgeMode::Kill,
+            EdgeMode::Kill,
+        );
+        system.add_emitter(shot(Vec2::new(50.0, 50.0), Vec2::new(600.0, 0.0), 1));
+        for _ in 0..60 {
+            system.update(DT, &[]);
+            let (position, prev_position) = (
+                system.particles.positions()[0],
+                system.particles.prev_positions()[0],
+            );
+            assert!((0.0..=100.0).contains(&position.x));
+            assert!(position.distance(prev_position) <= 10.0 + 1e-3);
+        }
+
+        // Spawned at the end of the first update, it then moves 590 units
+        assert_eq!(system.particle_count(), 1);
+        assert!((system.particles.positions()[0].x - 40.0).abs() < 1e-2);
+    }
+
+    #[test]
+    fn bouncing_loses_speed_into_and_along_the_edge() {
+        let bounce = EdgeMode::Bounce {
+            restitution: 0.5,
+            friction: 0.25,
+        };
+        let mut system = boxed(bounce, bounce, bounce, bounce);
+        system.add_emitter(shot(Vec2::new(50.0, 90.0), Vec2::new(60.0, 120.0), 1));
+        for _ in 0..10 {
+            system.update(DT, &[]);
+        }
+        let velocity = system.particles.velocities()[0];
+        assert!((velocity - Vec2::new(45.0, -60.0)).length() < 1e-3);
+        assert!(system.particles.positions()[0].y <= 100.0);
+    }
+
+    #[test]
+    fn clamped_particles_stop_at_the_edge() {
+        let mut system = boxed(
+            EdgeMode::Clamp,
+            EdgeMode::Clamp,
+            EdgeMode::Clamp,
+            EdgeMode::Clamp,
+        );
+        system.add_emitter(shot(Vec2::new(50.0, 50.0), Vec2::new(300.0, 300.0), 1));
+        for _ in 0..30 {
+            system.update(DT, &[]);
+        }
+        assert_eq!(system.particles.positions()[0], Vec2::splat(100.0));
+        assert_eq!(system.particles.velocities()[0], Vec2::ZERO);
+    }
+
+    #[test]
+    fn killed_particles_fire_death_sub_emitters() {
+        let mut system = boxed(
+            EdgeMode::Kill,
+            EdgeMode::Kill,
+            EdgeMode::Kill,
+            EdgeMode::Kill,
+        );
+        let mut sparks = Emitter::new(Vec2::ZERO);
+        sparks.settings.enabled = false;
+        sparks.settings.particle_lifetime = 0.01;
+        system.add_emitter(sparks);
+        let mut burst = shot(Vec2::new(50.0, 50.0), Vec2::new(0.0, -300.0), 20);
+        burst.settings.spread = 1.0;
+        burst
+            .settings
+            .sub_emitters
+            .push(SubEmitter::new(SubEmitterTrigger::Death, 0, 1));
+        system.add_emitter(burst);
+
+        let mut spark_count = 0;
+        for _ in 0..30 {
+            system.update(DT, &[]);
+            spark_count += system.stats().emitter_particles[0];
+        }
+        assert_eq!(system.particle_count(), 0);
+        assert_eq!(spark_count, 20);
+    }
+}
@@ -1,10 +1,12 @@
 //! Collision detection and spatial partitioning
 
+use crate::groups::GroupMask;
 use crate::physics::Vec2;
 use std::collections::HashMap;
 
 /// Axis-aligned bounding box
-#[derive(Clone, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AABB {
     pub min: Vec2,
     pub max: Vec2,
@@ -32,6 +34,7 @@ impl AABB {
 
 /// Line segment for outline representation
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LineSegment {
     pub start: Vec2,
     pub end: Vec2,
@@ -63,10 +66,13 @@ impl LineSegment {
 
 /// Detected person outline for particle collision
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Outline {
     pub segments: Vec<LineSegment>,
     pub bounds: AABB,
     pub velocity: Vec2,
+    /// Groups of the particles that collide with this outline
+    pub mask: GroupMask,
 }
 
 impl Outline {
@@ -77,6 +83,7 @@ impl Outline {
                 segments: Vec::new(),
                 bounds: AABB::new(Vec2::ZERO, Vec2::ZERO),
                 velocity: Vec2::ZERO,
+                mask: GroupMask::ALL,
             };
         }
 
@@ -96,6 +103,7 @@ impl Outline {
             segments,
             bounds: AABB::new(min, max),
             velocity: Vec2::ZERO,
+            mask: GroupMask::ALL,
         }
     }
 
@@ -133,7 +141,20 @@ impl Outline {
         let t = (qp.x * s.y - qp.y * s.x) / r_cross_s;
         let u = qp_cross_r / r_cross_s;
 
-        t >= 0.0 && t <= 1.0 && u >= 0.0 && u <= 1.0
+        (0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u)
+    }
+
+    /// Returns the point on the outline's edges closest to `point`
+    ///
+    /// Returns `None` for an outline without segments.
+    pub fn closest_point(&self, point: Vec2) -> Option<Vec2> {
+        self.segments
+            .iter()
+            .map(|segment| segment.closest_point(point))
+            .min_by(|a, b| {
+                a.distance_squared(point)
+                    .total_cmp(&b.distance_squared(point))
+            })
     }
 
     /// Returns the centroid of the outline
@@ -165,6 +186,27 @@ impl SpatialGrid {
         }
     }
 
+    /// Returns the edge length of a cell
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    /// Changes the edge length of the cells, removing all particles
+    pub fn set_cell_size(&mut self, cell_size: f32) {
+        self.cell_size = cell_size;
+        self.cells.clear();
+    }
+
+    /// Returns the width of the area covered by the grid
+    pub fn width(&self) -> f32 {
+        self.width
+    }
+
+    /// Returns the height of the area covered by the grid
+    pub fn height(&self) -> f32 {
+        self.height
+    }
+
     /// Clears all particles from the grid
     pub fn clear(&mut self) {
         self.cells.clear();
@@ -173,14 +215,24 @@ impl SpatialGrid {
     /// Inserts a particle into the grid
     pub fn insert(&mut self, particle_idx: usize, position: Vec2) {
         let cell = self.get_cell(position);
-        self.cells.entry(cell).or_insert_with(Vec::new).push(particle_idx);
+        self.cells.entry(cell).or_default().push(particle_idx);
     }
 
     /// Returns particle indices in cells near the given position
     pub fn query_nearby(&self, position: Vec2, radius: f32) -> Vec<usize> {
+        let mut result = Vec::new();
+        self.query_nearby_into(position, radius, &mut result);
+        result
+    }
+
+    /// Replaces the contents of `result` with the particle indices in cells
+    /// near the given position
+    ///
+    /// Reuses the allocation of `result`, for queries made every step.
+    pub fn query_nearby_into(&self, position: Vec2, radius: f32, result: &mut Vec<usize>) {
         let cell_radius = (radius / self.cell_size).ceil() as i32;
         let center_cell = self.get_cell(position);
-        let mut result = Vec::new();
+        result.clear();
 
         for dy in -cell_radius..=cell_radius {
             for dx in -cell_radius..=cell_radius {
@@ -190,8 +242,6 @@ impl SpatialGrid {
                 }
             }
         }
-
-        result
     }
 
     fn get_cell(&self, position: Vec2) -> (i32, i32) {
@@ -202,3 +252,72 @@ impl SpatialGrid {
     }
 }
 
+/// Particles of some groups binned into a `SpatialGrid` for neighbour queries
+///
+/// Grid cells, member list and query results are reused across steps, so
+/// rebuilding and querying every step does not allocate once warmed up.
+pub(crate) struct NeighbourGrid {
+    grid: SpatialGrid,
+    /// Dense indices of the particles in the grid
+    members: Vec<usize>,
+    /// Result of the latest neighbour query
+    neighbours: Vec<usize>,
+}
+
+impl Default for NeighbourGrid {
+    fn default() -> Self {
+        Self {
+            grid: SpatialGrid::new(0.0, 0.0, 1.0),
+            members: Vec::new(),
+            neighbours: Vec::new(),
+        }
+    }
+}
+
+impl NeighbourGrid {
+    /// Bins the particles in `mask` into cells `radius` wide
+    ///
+    /// Returns false if no particle is in `mask`.
+    pub(crate) fn rebuild(
+        &mut self,
+        mask: GroupMask,
+        radius: f32,
+        positions: &[Vec2],
+        groups: &[u32],
+    ) -> bool {
+        if self.grid.cell_size() != radius {
+            self.grid.set_cell_size(radius);
+        }
+        self.grid.clear();
+        self.members.clear();
+        for (index, &bits) in groups.iter().enumerate() {
+            if mask.intersects(GroupMask(bits)) {
+                self.members.push(index);
+                self.grid.insert(index, positions[index]);
+            }
+        }
+        !self.members.is_empty()
+    }
+
+    /// Returns the dense indices of the binned particles, in index order
+    pub(crate) fn members(&self) -> &[usize] {
+        &self.members
+    }
+
+    /// Calls `visit` with each member and the members in cells near it
+    ///
+    /// The candidates include the member itself and may lie up to a cell
+    /// beyond `radius`, so callers check the distance.
+    pub(crate) fn for_each(
+        &mut self,
+        positions: &[Vec2],
+        radius: f32,
+        mut visit: impl FnMut(usize, &[usize]),
+    ) {
+        for &i in &self.members {
+            self.grid
+                .query_nearby_into(positions[i], radius, &mut self.neighbours);
+            visit(i, &self.neighbours);
+        }
+    }
+}
@@ -0,0 +1,150 @@
+//! Keyframed curves and gradients evaluated over normalized time
+
+/// Piecewise linear curve mapping `[0, 1]` to a scalar
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(from = "Vec<(f32, f32)>", into = "Vec<(f32, f32)>")
+)]
+pub struct Curve {
+    /// `(time, value)` keyframes sorted by time
+    keys: Vec<(f32, f32)>,
+}
+
+impl Curve {
+    /// Creates a curve from `(time, value)` keyframes in any order
+    pub fn new(mut keys: Vec<(f32, f32)>) -> Self {
+        keys.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Self { keys }
+    }
+
+    /// Creates a curve with the same value everywhere
+    pub fn constant(value: f32) -> Self {
+        Self::new(vec![(0.0, value)])
+    }
+
+    /// Creates a curve going linearly from `start` to `end`
+    pub fn linear(start: f32, end: f32) -> Self {
+        Self::new(vec![(0.0, start), (1.0, end)])
+    }
+
+    /// Returns the keyframes sorted by time
+    pub fn keys(&self) -> &[(f32, f32)] {
+        &self.keys
+    }
+
+    /// Evaluates the curve at `t`, holding the end values outside the keys
+    ///
+    /// A curve without keys evaluates to 1, leaving what it scales unchanged.
+    pub fn evaluate(&self, t: f32) -> f32 {
+        let Some((index, t)) = segment(&self.keys, t, |key| key.0) else {
+            return 1.0;
+        };
+        match self.keys.get(index + 1) {
+            Some(next) => self.keys[index].1 + (next.1 - self.keys[index].1) * t,
+            None => self.keys[index].1,
+        }
+    }
+}
+
+impl From<Vec<(f32, f32)>> for Curve {
+    fn from(keys: Vec<(f32, f32)>) -> Self {
+        Self::new(keys)
+    }
+}
+
+impl From<Curve> for Vec<(f32, f32)> {
+    fn from(curve: Curve) -> Self {
+        curve.keys
+    }
+}
+
+/// Piecewise linear RGBA gradient over `[0, 1]`
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(from = "Vec<(f32, [f32; 4])>", into = "Vec<(f32, [f32; 4])>")
+)]
+pub struct Gradient {
+    /// `(time, color)` keyframes sorted by time
+    keys: Vec<(f32, [f32; 4])>,
+}
+
+impl Gradient {
+    /// Creates a gradient from `(time, color)` keyframes in any order
+    pub fn new(mut keys: Vec<(f32, [f32; 4])>) -> Self {
+        keys.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Self { keys }
+    }
+
+    /// Creates a gradient going linearly from `start` to `end`
+    pub fn linear(start: [f32; 4], end: [f32; 4]) -> Self {
+        Self::new(vec![(0.0, start), (1.0, end)])
+    }
+
+    /// Returns the keyframes sorted by time
+    pub fn keys(&self) -> &[(f32, [f32; 4])] {
+        &self.keys
+    }
+
+    /// Evaluates the gradient at `t`, holding the end colors outside the keys
+    ///
+    /// A gradient without keys evaluates to white.
+    pub fn evaluate(&self, t: f32) -> [f32; 4] {
+        let Some((index, t)) = segment(&self.keys, t, |key| key.0) else {
+            return [1.0; 4];
+        };
+        let from = self.keys[index].1;
+        match self.keys.get(index + 1) {
+            Some(&(_, to)) => std::array::from_fn(|c| from[c] + (to[c] - from[c]) * t),
+            None => from,
+        }
+    }
+}
+
+impl From<Vec<(f32, [f32; 4])>> for Gradient {
+    fn from(keys: Vec<(f32, [f32; 4])>) -> Self {
+        Self::new(keys)
+    }
+}
+
+impl From<Gradient> for Vec<(f32, [f32; 4])> {
+    fn from(gradient: Gradient) -> Self {
+        gradient.keys
+    }
+}
+
+/// Finds the key segment containing `t`
+///
+/// Returns the index of the segment's first key and the position of `t`
+/// within the segment, or `None` if there are no keys.
+fn segment<K>(keys: &[K], t: f32, time: impl Fn(&K) -> f32) -> Option<(usize, f32)> {
+    let first = keys.first()?;
+    // A NaN `t` is after no key, so it would find no segment either
+    if t <= time(first) || t.is_nan() {
+        return Some((0, 0.0));
+    }
+
+    // Index of the first key after `t`; at least 1 since `t` is past the first key
+    let next = keys.partition_point(|key| time(key) <= t);
+    if next == keys.len() {
+        return Some((keys.len() - 1, 0.0));
+    }
+
+    let (start, end) = (time(&keys[next - 1]), time(&keys[next]));
+    Some((next - 1, (t - start) / (end - start)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nan_evaluates_to_the_first_key() {
+        assert_eq!(Curve::linear(2.0, 4.0).evaluate(f32::NAN), 2.0);
+        let gradient = Gradient::linear([0.0; 4], [1.0; 4]);
+        assert_eq!(gradient.evaluate(f32::NAN), [0.0; 4]);
+    }
+}
@@ -0,0 +1,171 @@
+//! Emission scheduling: timed bursts, rate envelopes and sub-emitters
+
+use crate::curves::Curve;
+use crate::particles::Particle;
+
+/// Burst of particles fired at a point in emitter time, optionally repeating
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Burst {
+    /// Emitter time of the first burst in seconds
+    pub time: f32,
+    /// Particles spawned per burst
+    pub count: u32,
+    /// Seconds between repeats
+    pub interval: f32,
+    /// Number of bursts to fire; `None` repeats forever
+    pub cycles: Option<u32>,
+    /// Cycles fired so far; run-time state, so not serialized
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) fired: u32,
+}
+
+impl Burst {
+    /// Creates a single burst of `count` particles at `time`
+    pub fn new(time: f32, count: u32) -> Self {
+        Self {
+            time,
+            count,
+            interval: 0.0,
+            cycles: Some(1),
+            fired: 0,
+        }
+    }
+
+    /// Repeats the burst every `interval` seconds, `cycles` times in total
+    pub fn repeating(mut self, interval: f32, cycles: Option<u32>) -> Self {
+        self.interval = interval;
+        self.cycles = cycles;
+        self
+    }
+
+    /// Returns the number of particles due up to emitter time `now`
+    pub(crate) fn due(&mut self, now: f32) -> u32 {
+        // Without an interval every cycle would be due at once
+        let cycles = if self.interval > 0.0 {
+            self.cycles
+        } else {
+            Some(self.cycles.map_or(1, |cycles| cycles.min(1)))
+        };
+
+        let mut count = 0;
+        while cycles.is_none_or(|cycles| self.fired < cycles)
+            && self.time + self.interval * self.fired as f32 <= now
+        {
+            self.fired += 1;
+            count += self.count;
+        }
+        count
+    }
+
+    /// Forgets which cycles have fired, e.g. when the emitter loops
+    pub fn reset(&mut self) {
+        self.fired = 0;
+    }
+}
+
+/// Multiplier on an emitter's rate over emitter time
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RateEnvelope {
+    /// Rate multiplier over normalized envelope time
+    pub curve: Curve,
+    /// Length of the envelope in seconds
+    pub duration: f32,
+    /// Restart the envelope after `duration` instead of holding its end value
+    pub looping: bool,
+}
+
+impl RateEnvelope {
+    /// Creates an envelope playing `curve` once over `duration` seconds
+    pub fn new(curve: Curve, duration: f32) -> Self {
+        Self {
+            curve,
+            duration,
+            looping: false,
+        }
+    }
+
+    /// Makes the envelope repeat every `duration` seconds
+    pub fn looping(mut self) -> Self {
+        self.looping = true;
+        self
+    }
+
+    /// Returns the rate multiplier at emitter time `time`
+    pub fn evaluate(&self, time: f32) -> f32 {
+        if self.duration <= 0.0 {
+            return self.curve.evaluate(1.0);
+        }
+
+        let t = time / self.duration;
+        self.curve
+            .evaluate(if self.looping { t.fract() } else { t })
+    }
+}
+
+/// Particle event that fires a sub-emitter
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SubEmitterTrigger {
+    /// The particle was just spawned
+    Birth,
+    /// The particle's life ran out
+    Death,
+    /// The particle hit one of the system's colliders
+    Collision,
+}
+
+/// Burst of child particles spawned where a parent particle had an event
+///
+/// Children come from another emitter of the same system, usually a disabled
+/// one that only serves as a template. They always start at the parent's
+/// position; velocity, color and size are blended in by the inherit factors.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SubEmitter {
+    /// Event that fires this sub-emitter
+    pub trigger: SubEmitterTrigger,
+    /// Index of the emitter that spawns the children
+    pub emitter: usize,
+    /// Chance in `[0, 1]` that an event spawns children
+    pub probability: f32,
+    /// Children spawned per event
+    pub count: u32,
+    /// Fraction of the parent's velocity added to each child's
+    pub inherit_velocity: f32,
+    /// Blend from the child's color (0) to the color the parent spawned with (1)
+    pub inherit_color: f32,
+    /// Blend from the child's size (0) to the parent's (1)
+    pub inherit_size: f32,
+}
+
+impl SubEmitter {
+    /// Creates a sub-emitter spawning `count` children from `emitter` on `trigger`
+    ///
+    /// Children always fire and inherit the parent's velocity and color
+    /// but keep their own size.
+    pub fn new(trigger: SubEmitterTrigger, emitter: usize, count: u32) -> Self {
+        Self {
+            trigger,
+            emitter,
+            probability: 1.0,
+            count,
+            inherit_velocity: 1.0,
+            inherit_color: 1.0,
+            inherit_size: 0.0,
+        }
+    }
+
+    /// Blends the parent's velocity, color and size into a freshly spawned child
+    pub(crate) fn inherit(&self, parent: &Particle, child: &mut Particle) {
+        let velocity = child.vel() + parent.vel() * self.inherit_velocity;
+        child.velocity = velocity.into();
+
+        for (channel, parent) in child.color.iter_mut().zip(parent.color) {
+            *channel += (parent - *channel) * self.inherit_color;
+        }
+
+        child.size += (parent.size - child.size) * self.inherit_size;
+    }
+}
@@ -0,0 +1,188 @@
+//! Events reported by the simulation step
+
+use crate::particles::EmitterId;
+use crate::physics::Vec2;
+use crate::storage::ParticleId;
+
+/// Something that happened to a particle during `ParticleSystem::update`
+///
+/// Recorded while `config.record_events` is set and kept until drained
+/// with `ParticleSystem::drain_events`. Handles of particles that died stay
+/// in the event so they can be matched with their `Spawned` event.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ParticleEvent {
+    /// A particle was emitted
+    Spawned {
+        particle: ParticleId,
+        /// Handle of the emitter, `None` for emitters added without one
+        emitter: Option<EmitterId>,
+        position: Vec2,
+    },
+
+    /// A particle ran out of life, was recycled to make room or was removed
+    /// along with its emitter
+    Died {
+        particle: ParticleId,
+        emitter: Option<EmitterId>,
+        position: Vec2,
+    },
+
+    /// A particle was pushed out of a collider
+    Collided {
+        particle: ParticleId,
+        /// Index into `ParticleSystem::colliders`
+        collider: usize,
+        /// Point on the outline the particle was moved to
+        position: Vec2,
+        /// Outward surface normal at `position`
+        normal: Vec2,
+        /// Speed towards the surface relative to the outline's motion
+        speed: f32,
+    },
+
+    /// A particle moved into a zone
+    Entered {
+        particle: ParticleId,
+        /// Index into `ParticleSystem::zones`
+        zone: usize,
+        position: Vec2,
+    },
+
+    /// A particle moved out of a zone
+    Left {
+        particle: ParticleId,
+        zone: usize,
+        position: Vec2,
+    },
+}
+
+impl ParticleEvent {
+    /// Returns the handle of the particle the event is about
+    pub fn particle(&self) -> ParticleId {
+        match *self {
+            Self::Spawned { particle, .. }
+            | Self::Died { particle, .. }
+            | Self::Collided { particle, .. }
+            | Self::Entered { particle, .. }
+            | Self::Left { particle, .. } => particle,
+        }
+    }
+
+    /// Returns where the event happened
+    pub fn position(&self) -> Vec2 {
+        match *self {
+            Self::Spawned { position, .. }
+            | Self::Died { position, .. }
+            | Self::Collided { position, .. }
+            | Self::Entered { position, .. }
+            | Self::Left { position, .. } => position,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collision::Outline;
+    use crate::particles::{Emitter, ParticleConfig, ParticleSystem};
+    use std::collections::HashSet;
+
+    /// Axis-aligned box as an outline
+    fn rectangle(min: Vec2, max: Vec2) -> Outline {
+        Outline::from_points(vec![
+            min,
+            Vec2::new(max.x, min.y),
+            max,
+            Vec2::new(min.x, max.y),
+        ])
+    }
+
+    /// Particles fly right through a zone into a wall and die on the way back
+    fn shooting_gallery() -> (ParticleSystem, Option<EmitterId>) {
+        let mut system = ParticleSystem::with_config(ParticleConfig {
+            seed: Some(8),
+            gravity: Vec2::ZERO,
+            drag_coefficient: 0.0,
+            record_events: true,
+            ..ParticleConfig::default()
+        });
+        let mut emitter = Emitter::new(Vec2::ZERO);
+        emitter.settings.rate = 10.0;
+        emitter.settings.spread = 0.0;
+        emitter.settings.initial_velocity = 100.0;
+        emitter.settings.particle_lifetime = 3.0;
+        let id = system.add_emitter(emitter);
+        system
+            .zones
+            .push(rectangle(Vec2::new(50.0, -10.0), Vec2::new(100.0, 10.0)));
+        system
+            .colliders
+            .push(rectangle(Vec2::new(200.0, -10.0), Vec2::new(260.0, 10.0)));
+        (system, id)
+    }
+
+    #[test]
+    fn updates_report_spawns_deaths_collisions_and_zones() {
+        let (mut system, id) = shooting_gallery();
+        let mut events = Vec::new();
+        for _ in 0..240 {
+            system.update(1.0 / 60.0, &[]);
+            events.extend(system.drain_events());
+        }
+        assert!(system.events().is_empty());
+
+        let mut spawned = HashSet::new();
+        let (mut died, mut collided, mut entered, mut left) = (0, 0, 0, 0);
+        for event in events {
+            match event {
+                ParticleEvent::Spawned {
+                    particle, emitter, ..
+                } => {
+                    assert_eq!(emitter, id);
+                    spawned.insert(particle);
+                }
+                ParticleEvent::Died { particle, .. } => {
+                    assert!(spawned.contains(&particle), "died before it spawned");
+                    died += 1;
+                }
+                ParticleEvent::Collided {
+                    collider,
+                    normal,
+                    speed,
+                    position,
+                    ..
+                } => {
+                    assert_eq!(collider, 0);
+                    assert!(normal.x < -0.99, "normal {:?} faces away", normal);
+                    assert!((speed - 100.0).abs() < 1.0);
+                    assert!((position.x - 200.0).abs() < 1e-3);
+                    collided += 1;
+                }
+                ParticleEvent::Entered { zone, position, .. } => {
+                    assert_eq!(zone, 0);
+                    assert!(position.x >= 50.0);
+                    entered += 1;
+                }
+                ParticleEvent::Left { position, .. } => {
+                    assert!(position.x > 100.0);
+                    left += 1;
+                }
+            }
+        }
+
+        assert_eq!(spawned.len(), died + system.particle_count());
+        assert!(collided > 10 && entered >= left && left > 20);
+    }
+
+    #[test]
+    fn nothing_is_recorded_unless_asked_for() {
+        let (mut system, _) = shooting_gallery();
+        system.config.record_events = false;
+        for _ in 0..240 {
+            system.update(1.0 / 60.0, &[]);
+        }
+        assert!(system.particle_count() > 0);
+        assert!(system.events().is_empty());
+    }
+}
@@ -0,0 +1,358 @@
+//! Boids that flock together and flee from predators
+//!
+//! Each flock steers its particles by Reynolds' three rules over the
+//! flockmates they can see, found through a `SpatialGrid` with cells one
+//! perception radius wide. Colliders and repulsor forces act as predators
+//! the flock scatters away from before regrouping.
+
+use crate::collision::{NeighbourGrid, Outline};
+use crate::groups::GroupMask;
+use crate::physics::{Force, Vec2};
+use std::f32::consts::TAU;
+
+/// Flocking behaviour of the particles in some groups
+///
+/// Particles only flock with members of the same flock. Give an emitter a
+/// group of its own to flock its particles apart from the rest.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct Flock {
+    /// Groups whose particles belong to the flock
+    pub mask: GroupMask,
+    /// Distance within which flockmates are seen, in pixels
+    pub perception_radius: f32,
+    /// Field of view in radians, centred on the direction of travel
+    pub perception_angle: f32,
+    /// Weight of keeping clear of flockmates within half the perception radius
+    pub separation: f32,
+    /// Weight of matching the flockmates' heading
+    pub alignment: f32,
+    /// Weight of moving towards the flockmates' centre
+    pub cohesion: f32,
+    /// Weight of fleeing from colliders and repulsors
+    pub avoidance: f32,
+    /// Distance from a collider at which the flock starts to flee
+    pub predator_radius: f32,
+    /// Fastest a member flies
+    pub max_speed: f32,
+    /// Largest acceleration each rule may steer with
+    pub max_force: f32,
+}
+
+impl Default for Flock {
+    fn default() -> Self {
+        Self {
+            mask: GroupMask::NONE,
+            perception_radius: 40.0,
+            perception_angle: 1.5 * std::f32::consts::PI,
+            separation: 1.5,
+            alignment: 1.0,
+            cohesion: 1.0,
+            avoidance: 4.0,
+            predator_radius: 80.0,
+            max_speed: 150.0,
+            max_force: 300.0,
+        }
+    }
+}
+
+impl Flock {
+    /// Returns the acceleration that turns `velocity` towards `direction`
+    /// at full speed, limited to `max_force`
+    fn steer(&self, direction: Vec2, velocity: Vec2) -> Vec2 {
+        if direction == Vec2::ZERO {
+            return Vec2::ZERO;
+        }
+        (direction.normalize() * self.max_speed - velocity).clamp_length_max(self.max_force)
+    }
+
+    /// Returns true if a member at rest or heading along `velocity` sees `offset`
+    fn sees(&self, velocity: Vec2, offset: Vec2) -> bool {
+        if self.perception_angle >= TAU || velocity == Vec2::ZERO || offset == Vec2::ZERO {
+            return true;
+        }
+        velocity.angle_to(offset).abs() <= 0.5 * self.perception_angle
+    }
+}
+
+/// Position and reach of a repulsor force, looking through masks
+///
+/// Returns `None` for other forces and for repulsors masked off `groups`.
+fn repulsor(force: &Force, groups: GroupMask) -> Option<(Vec2, f32)> {
+    match force {
+        Force::Repulsor {
+            position, radius, ..
+        } => Some((*position, *radius)),
+        Force::Masked { force, mask } if mask.intersects(groups) => repulsor(force, groups),
+        _ => None,
+    }
+}
+
+/// Neighbour grid and per-particle buffers reused across steps
+#[derive(Default)]
+pub(crate) struct FlockSolver {
+    /// The current flock's members
+    grid: NeighbourGrid,
+    accelerations: Vec<Vec2>,
+}
+
+impl FlockSolver {
+    /// Steers the members of each flock and limits them to its top speed
+    ///
+    /// Only the velocities of particles in a flock's mask change. Colliders
+    /// and forces are predators of the flocks whose groups they act on.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn step(
+        &mut self,
+        flocks: &[Flock],
+        colliders: &[Outline],
+        forces: &[Force],
+        positions: &[Vec2],
+        velocities: &mut [Vec2],
+        groups: &[u32],
+        dt: f32,
+    ) {
+        for flock in flocks {
+            let radius = flock.perception_radius;
+            if flock.mask == GroupMask::NONE || radius <= 0.0 {
+                continue;
+            }
+
+            if !self.grid.rebuild(flock.mask, radius, positions, groups) {
+                continue;
+            }
+
+            let crowded_sq = 0.25 * radius * radius;
+            self.accelerations.clear();
+            self.grid.for_each(positions, radius, |i, neighbours| {
+                let (position, velocity) = (positions[i], velocities[i]);
+                let (mut away, mut heading, mut centre, mut seen) =
+                    (Vec2::ZERO, Vec2::ZERO, Vec2::ZERO, 0);
+                for &j in neighbours {
+                    let offset = positions[j] - position;
+                    let distance_sq = offset.length_squared();
+                    if j == i || distance_sq >= radius * radius || !flock.sees(velocity, offset) {
+                        continue;
+                    }
+
+                    // Closer flockmates push harder
+                    if distance_sq > 0.0 && distance_sq < crowded_sq {
+                        away -= offset / distance_sq;
+                    }
+                    heading += velocities[j];
+                    centre += positions[j];
+                    seen += 1;
+                }
+
+                let mut acceleration = flock.separation * flock.steer(away, velocity);
+                if seen > 0 {
+                    acceleration += flock.alignment * flock.steer(heading, velocity);
+                    acceleration +=
+                        flock.cohesion * flock.steer(centre / seen as f32 - position, velocity);
+                }
+
+                // Flee harder the closer the predator
+                let mut flee = Vec2::ZERO;
+                for outline in colliders
+                    .iter()
+                    .filter(|outline| outline.mask.intersects(flock.mask))
+                {
+                    let Some(closest) = outline.closest_point(position) else {
+                        continue;
+                    };
+                    let distance = closest.distance(position);
+                    if distance < flock.predator_radius {
+                        // Members caught inside run out through the nearest edge
+                        let direction = if outline.contains(position) {
+                            closest - position
+                        } else {
+                            position - closest
+                        };
+                        flee += direction.normalize_or_zero()
+                            * (1.0 - distance / flock.predator_radius);
+                    }
+                }
+                for (centre, reach) in forces
+                    .iter()
+                    .filter_map(|force| repulsor(force, flock.mask))
+                {
+                    let distance = position.distance(centre);
+                    if distance < reach {
+                        flee += (position - centre).normalize_or_zero() * (1.0 - distance / reach);
+                    }
+                }
+                let urgency = flee.length().min(1.0);
+                acceleration += flock.avoidance * urgency * flock.steer(flee, velocity);
+
+                self.accelerations.push(acceleration);
+            });
+
+            for (&i, &acceleration) in self.grid.members().iter().zip(&self.accelerations) {
+                velocities[i] =
+                    (velocities[i] + acceleration * dt).clamp_length_max(flock.max_speed);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::emission::Burst;
+    use crate::particles::{Emitter, ParticleConfig, ParticleSystem};
+    use crate::shapes::EmitterShape;
+
+    const DT: f32 = 1.0 / 60.0;
+    const STARLINGS: GroupMask = GroupMask::group(2);
+    const CENTRE: Vec2 = Vec2::new(400.0, 300.0);
+
+    /// Starlings and unflocked dust scattered around the centre, a step in
+    fn sky(flocks: Vec<Flock>) -> ParticleSystem {
+        let mut system = ParticleSystem::with_config(ParticleConfig {
+            seed: Some(9),
+            gravity: Vec2::ZERO,
+            drag_coefficient: 0.0,
+            flocks,
+            ..ParticleConfig::default()
+        });
+        for groups in [STARLINGS, GroupMask::DEFAULT] {
+            let mut emitter = Emitter::new(CENTRE);
+            emitter.settings.rate = 0.0;
+            emitter.settings.shape = EmitterShape::Circle { radius: 120.0 };
+            emitter.settings.spread = TAU;
+            emitter.settings.initial_velocity = 80.0;
+            emitter.settings.particle_lifetime = 100.0;
+            emitter.settings.groups = groups;
+            emitter.settings.bursts.push(Burst::new(0.0, 150));
+            system.add_emitter(emitter);
+        }
+        run(&mut system, DT, &[]);
+        system
+    }
+
+    fn starlings() -> Flock {
+        Flock {
+            mask: STARLINGS,
+            ..Flock::default()
+        }
+    }
+
+    fn run(system: &mut ParticleSystem, seconds: f32, forces: &[Force]) {
+        for _ in 0..(seconds / DT).round() as u32 {
+            system.update(DT, forces);
+        }
+    }
+
+    /// Particles of `groups` as (position, velocity) pairs
+    fn members(system: &ParticleSystem, groups: GroupMask) -> Vec<(Vec2, Vec2)> {
+        let particles = &system.particles;
+        (0..particles.len())
+            .filter(|&i| groups.intersects(GroupMask(particles.groups()[i])))
+            .map(|i| (particles.positions()[i], particles.velocities()[i]))
+            .collect()
+    }
+
+    /// Mean cosine between each member's heading and that of its neighbours
+    fn local_alignment(system: &ParticleSystem, groups: GroupMask) -> f32 {
+        let members = members(system, groups);
+        let mut total = 0.0;
+        for &(position, velocity) in &members {
+            let heading: Vec2 = members
+                .iter()
+                .filter(|(other, _)| *other != position && other.distance(position) < 40.0)
+                .map(|(_, velocity)| velocity.normalize_or_zero())
+                .sum();
+            total += velocity
+                .normalize_or_zero()
+                .dot(heading.normalize_or_zero());
+        }
+        total / members.len() as f32
+    }
+
+    /// Members of `groups` within `radius` of `point`
+    fn near(system: &ParticleSystem, groups: GroupMask, point: Vec2, radius: f32) -> usize {
+        members(system, groups)
+            .iter()
+            .filter(|(position, _)| position.distance(point) < radius)
+            .count()
+    }
+
+    /// Square visitor silhouette around a point
+    fn visitor(centre: Vec2) -> Outline {
+        Outline::from_points(vec![
+            centre + Vec2::new(-30.0, -60.0),
+            centre + Vec2::new(30.0, -60.0),
+            centre + Vec2::new(30.0, 60.0),
+            centre + Vec2::new(-30.0, 60.0),
+        ])
+    }
+
+    /// Centroid of the members of `groups`
+    fn centroid(system: &ParticleSystem, groups: GroupMask) -> Vec2 {
+        let members = members(system, groups);
+        members.iter().map(|(position, _)| *position).sum::<Vec2>() / members.len() as f32
+    }
+
+    #[test]
+    fn flocks_align_within_their_top_speed() {
+        let mut system = sky(vec![starlings()]);
+        assert!(local_alignment(&system, STARLINGS) < 0.3);
+        run(&mut system, 5.0, &[]);
+        assert!(local_alignment(&system, STARLINGS) > 0.9);
+        let top_speed = members(&system, STARLINGS)
+            .iter()
+            .map(|(_, velocity)| velocity.length())
+            .fold(0.0, f32::max);
+        assert!(top_speed <= Flock::default().max_speed + 1e-3);
+
+        // Dust moves exactly as it would without any flock
+        let mut still = sky(Vec::new());
+        run(&mut still, 5.0, &[]);
+        assert_eq!(
+            members(&still, GroupMask::DEFAULT),
+            members(&system, GroupMask::DEFAULT)
+        );
+    }
+
+    #[test]
+    fn flocks_flee_predators_and_regroup() {
+        let mut system = sky(vec![starlings()]);
+        let mut brave = sky(vec![Flock {
+            avoidance: 0.0,
+            ..starlings()
+        }]);
+        run(&mut system, 5.0, &[]);
+        run(&mut brave, 5.0, &[]);
+
+        // A repulsor scares the flock away, even one too weak to push
+        let predator = centroid(&system, STARLINGS);
+        let scare = [Force::Repulsor {
+            position: predator,
+            strength: 0.0,
+            radius: 100.0,
+        }];
+        run(&mut system, 2.0, &scare);
+        run(&mut brave, 2.0, &scare);
+        assert!(
+            near(&system, STARLINGS, predator, 100.0) * 4
+                < near(&brave, STARLINGS, predator, 100.0)
+        );
+
+        // So does a visitor's silhouette
+        let visitor_at = centroid(&system, STARLINGS);
+        system.colliders.push(visitor(visitor_at));
+        brave.colliders.push(visitor(visitor_at));
+        run(&mut system, 1.0, &[]);
+        run(&mut brave, 1.0, &[]);
+        assert!(
+            near(&system, STARLINGS, visitor_at, 80.0) * 2
+                < near(&brave, STARLINGS, visitor_at, 80.0)
+        );
+
+        // Once the visitor leaves the flock regroups
+        system.colliders.clear();
+        run(&mut system, 4.0, &[]);
+        assert!(local_alignment(&system, STARLINGS) > 0.9);
+    }
+}
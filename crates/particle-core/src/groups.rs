@@ -0,0 +1,70 @@
+//! Particle groups and the masks that select them
+
+use std::ops::{BitAnd, BitOr, Not};
+
+/// Set of up to 32 particle groups
+///
+/// Emitters tag their particles with a set of groups; forces and colliders
+/// carry a mask and only act on particles sharing at least one group with it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GroupMask(pub u32);
+
+impl GroupMask {
+    /// No groups; a force with this mask affects nothing
+    pub const NONE: Self = Self(0);
+    /// Every group; the default mask of forces and colliders
+    pub const ALL: Self = Self(u32::MAX);
+    /// Group 0, which particles belong to unless their emitter says otherwise
+    pub const DEFAULT: Self = Self(1);
+
+    /// Returns the mask holding only group `index`, from 0 to 31
+    pub const fn group(index: u32) -> Self {
+        Self(1 << index)
+    }
+
+    /// Returns the raw bits, one per group
+    pub const fn bits(self) -> u32 {
+        self.0
+    }
+
+    /// Returns true if the masks share a group
+    pub const fn intersects(self, other: Self) -> bool {
+        self.0 & other.0 != 0
+    }
+
+    /// Returns true if every group of `other` is in this mask
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl Default for GroupMask {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl BitOr for GroupMask {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitAnd for GroupMask {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self {
+        Self(self.0 & rhs.0)
+    }
+}
+
+impl Not for GroupMask {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        Self(!self.0)
+    }
+}
@@ -0,0 +1,226 @@
+//! Live reloading of scene files for native builds
+//!
+//! The watcher polls the file's modification time and size, which works the
+//! same on every platform and with editors that save by replacing the file.
+//! Polling once per frame costs one `stat` call.
+
+use crate::particles::ParticleSystem;
+use crate::physics::Force;
+use crate::scene::{Scene, SceneError};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Watches a scene file and applies its changes to a running system
+pub struct SceneWatcher {
+    path: PathBuf,
+    /// Modification time and size seen by the last poll, `None` if missing
+    stamp: Option<(SystemTime, u64)>,
+    polled: bool,
+    /// Last scene that loaded successfully
+    scene: Option<Scene>,
+}
+
+impl SceneWatcher {
+    /// Watches the scene file at `path`; the first poll loads it
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            stamp: None,
+            polled: false,
+            scene: None,
+        }
+    }
+
+    /// Returns the path of the watched file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the last scene that loaded successfully
+    pub fn scene(&self) -> Option<&Scene> {
+        self.scene.as_ref()
+    }
+
+    /// Loads the scene if the file changed since the last poll
+    ///
+    /// Returns `None` while the file is unchanged. An error is reported
+    /// once per change; the last good scene stays available in `scene`.
+    pub fn poll(&mut self) -> Option<Result<&Scene, SceneError>> {
+        let stamp = fs::metadata(&self.path)
+            .and_then(|metadata| Ok((metadata.modified()?, metadata.len())))
+            .ok();
+        if self.polled && stamp == self.stamp {
+            return None;
+        }
+
+        self.polled = true;
+        self.stamp = stamp;
+        match Scene::load(&self.path) {
+            Ok(scene) => Some(Ok(self.scene.insert(scene))),
+            Err(error) => Some(Err(error)),
+        }
+    }
+
+    /// Applies the scene to `system` and `forces` if the file changed
+    ///
+    /// Emitters are updated in place, see `ParticleSystem::apply_scene`.
+    /// On error the system keeps running the last good scene untouched.
+    pub fn reload(
+        &mut self,
+        system: &mut ParticleSystem,
+        forces: &mut Vec<Force>,
+    ) -> Option<Result<(), SceneError>> {
+        Some(
+            self.poll()?
+                .map(|scene| *forces = system.apply_scene(scene)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+    use std::time::Duration;
+
+    /// Scene with a fountain of the given rate and optional extra emitter and force
+    fn scene(rate: f32, extras: bool) -> String {
+        let (sparkles, attractor) = if extras {
+            (
+                r#", { "position": [600.0, 300.0], "rate": 50.0, "particle_lifetime": 10.0 }"#,
+                r#", { "Attractor": { "position": [400.0, 300.0], "strength": 50000.0, "radius": 200.0 } }"#,
+            )
+        } else {
+            ("", "")
+        };
+        format!(
+            r#"{{
+  "version": 1,
+  "config": {{ "seed": 3 }},
+  "emitters": [
+    {{ "position": [200.0, 500.0], "rate": {}, "particle_lifetime": 10.0 }}{}
+  ],
+  "forces": [
+    {{ "Wind": {{ "direction": [1.0, 0.0], "strength": 10.0, "turbulence": 0.0 }} }}{}
+  ]
+}}"#,
+            rate, sparkles, attractor
+        )
+    }
+
+    /// Scene file in the temp directory, deleted when dropped
+    struct SceneFile {
+        path: PathBuf,
+        saves: u64,
+    }
+
+    impl SceneFile {
+        fn new(name: &str) -> Self {
+            let file = format!("hot-reload-{}-{}.json", name, std::process::id());
+            Self {
+                path: std::env::temp_dir().join(file),
+                saves: 0,
+            }
+        }
+
+        /// Saves the scene as an editor would, with a fresh modification time
+        fn save(&mut self, contents: &str) {
+            fs::write(&self.path, contents).unwrap();
+            self.saves += 1;
+            let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000 + self.saves);
+            File::options()
+                .write(true)
+                .open(&self.path)
+                .unwrap()
+                .set_modified(modified)
+                .unwrap();
+        }
+    }
+
+    impl Drop for SceneFile {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+
+    fn run(system: &mut ParticleSystem, forces: &[Force], steps: usize) {
+        for _ in 0..steps {
+            system.update(1.0 / 60.0, forces);
+        }
+    }
+
+    #[test]
+    fn edits_apply_in_place() {
+        let mut file = SceneFile::new("edits");
+        file.save(&scene(100.0, false));
+
+        // The first poll loads the scene into the fresh system
+        let mut watcher = SceneWatcher::new(&file.path);
+        let mut system = ParticleSystem::new();
+        let mut forces = Vec::new();
+        assert_eq!(watcher.reload(&mut system, &mut forces), Some(Ok(())));
+        assert_eq!(forces.len(), 1);
+        for _ in 0..60 {
+            run(&mut system, &forces, 1);
+            assert!(watcher.reload(&mut system, &mut forces).is_none());
+        }
+        let before = system.particles.ids().to_vec();
+        let time = system.emitters[0].time();
+
+        // Running emitters are updated and every particle is kept
+        file.save(&scene(300.0, true));
+        assert_eq!(watcher.reload(&mut system, &mut forces), Some(Ok(())));
+        assert_eq!(system.particles.ids(), &before[..]);
+        assert_eq!(system.emitters.len(), 2);
+        assert_eq!(system.emitters[0].settings.rate, 300.0);
+        assert_eq!(system.emitters[0].time(), time);
+        assert_eq!(forces.len(), 2);
+        run(&mut system, &forces, 60);
+        assert!(before.iter().all(|&id| system.particles.contains(id)));
+
+        // Removing an emitter takes only its particles with it
+        file.save(&scene(300.0, false));
+        let count = system.particle_count();
+        let sparkles = system
+            .particles
+            .emitters()
+            .iter()
+            .filter(|&&emitter| emitter == 1)
+            .count();
+        assert!(sparkles > 0);
+        assert_eq!(watcher.reload(&mut system, &mut forces), Some(Ok(())));
+        assert_eq!(system.emitters.len(), 1);
+        assert_eq!(system.particle_count(), count - sparkles);
+    }
+
+    #[test]
+    fn broken_and_deleted_files_are_reported_once() {
+        let mut file = SceneFile::new("errors");
+        file.save(&scene(300.0, true));
+        let mut watcher = SceneWatcher::new(&file.path);
+        let mut system = ParticleSystem::new();
+        let mut forces = Vec::new();
+        assert_eq!(watcher.reload(&mut system, &mut forces), Some(Ok(())));
+
+        // The last good scene keeps running
+        file.save(&scene(500.0, true).replace("\"rate\": 500", "\"rate\": 500,,"));
+        let error = watcher
+            .reload(&mut system, &mut forces)
+            .unwrap()
+            .unwrap_err();
+        assert!(matches!(error, SceneError::Syntax { line: 5, .. }));
+        assert!(watcher.reload(&mut system, &mut forces).is_none());
+        assert_eq!(system.emitters[0].settings.rate, 300.0);
+        assert_eq!(watcher.scene().unwrap().emitters[0].rate, 300.0);
+        assert_eq!(forces.len(), 2);
+
+        fs::remove_file(&file.path).unwrap();
+        let error = watcher
+            .reload(&mut system, &mut forces)
+            .unwrap()
+            .unwrap_err();
+        assert!(matches!(error, SceneError::Io(_)));
+        assert!(watcher.reload(&mut system, &mut forces).is_none());
+    }
+}
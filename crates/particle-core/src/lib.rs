@@ -1,16 +1,59 @@
 //! Core particle engine for movement-particles
 //! 
 //! This crate provides the fundamental particle system implementation including:
-//! - Particle lifecycle management
-//! - Physics simulation (forces, velocity, acceleration)
+//! - Particle lifecycle management and over-lifetime curves
+//! - Continuous, scheduled and one-shot burst emission with sub-emitters
+//! - Physics simulation (forces, velocity, acceleration), batched over SIMD lanes
 //! - Collision detection with outlines
+//! - Smoothed-particle hydrodynamics for groups that behave as a liquid
+//! - Flocking boids that scatter from outlines and repulsors
+//! - World bounds that wrap, bounce, clamp or kill particles at the edges
+//! - Event stream of spawns, deaths, collisions and zone crossings
+//! - Per-step statistics and phase timings
+//! - Particle groups that select which forces and colliders apply
 //! - Spatial partitioning for optimization
+//! - Structure-of-arrays storage for cache-friendly updates and GPU upload
+//! - Snapshots of the whole simulation state with the `serde` feature
+//! - Declarative JSON scenes with the `scene` feature, reloaded live with `hot-reload`
 
 pub mod particles;
 pub mod physics;
+pub mod bounds;
 pub mod collision;
+pub mod curves;
+pub mod emission;
+pub mod events;
+pub mod flocking;
+pub mod fluid;
+pub mod groups;
+#[cfg(feature = "hot-reload")]
+pub mod hot_reload;
+pub mod shapes;
+#[cfg(feature = "scene")]
+pub mod scene;
+mod simd;
+#[cfg(feature = "serde")]
+pub mod snapshot;
+pub mod stats;
+pub mod storage;
 
 // Re-export commonly used types
-pub use particles::{Particle, ParticleSystem, Emitter};
-pub use physics::{Force, Vec2};
-pub use collision::{Outline, SpatialGrid};
+pub use particles::{Dynamics, EmitterId, EmitterSettings, OverflowPolicy, Particle, ParticleConfig, ParticleRng, ParticleSystem, Emitter};
+pub use physics::{Drag, DragModel, Force, Integrator, IntegratorScratch, Vec2};
+pub use bounds::{EdgeMode, WorldBounds};
+pub use collision::{AABB, Outline, SpatialGrid};
+pub use curves::{Curve, Gradient};
+pub use emission::{Burst, RateEnvelope, SubEmitter, SubEmitterTrigger};
+pub use events::ParticleEvent;
+pub use flocking::Flock;
+pub use fluid::{Fluid, Kernel};
+pub use groups::GroupMask;
+pub use shapes::{EmitterShape, ShapeSample, ShapeSampling};
+pub use stats::{PhaseTimings, StepStats};
+pub use storage::{ParticleId, ParticleStorage};
+#[cfg(feature = "serde")]
+pub use snapshot::{Snapshot, SnapshotError};
+#[cfg(feature = "scene")]
+pub use scene::{Scene, SceneError};
+#[cfg(feature = "hot-reload")]
+pub use hot_reload::SceneWatcher;
@@ -1,11 +1,49 @@
 //! Particle system implementation
 
-use crate::physics::{Force, Vec2};
-use rand::Rng;
+use crate::bounds::{EdgeMode, WorldBounds};
+use crate::collision::Outline;
+use crate::curves::{Curve, Gradient};
+use crate::emission::{Burst, RateEnvelope, SubEmitter, SubEmitterTrigger};
+use crate::events::ParticleEvent;
+use crate::flocking::{Flock, FlockSolver};
+use crate::fluid::{Fluid, FluidSolver};
+use crate::groups::GroupMask;
+use crate::physics::{Drag, DragModel, Force, Integrator, IntegratorScratch, Vec2};
+use crate::shapes::{EmitterShape, ShapeSampling};
+#[cfg(feature = "serde")]
+use crate::snapshot::{Snapshot, SnapshotError, SNAPSHOT_VERSION};
+use crate::stats::{PhaseTimings, StepStats, Stopwatch};
+use crate::storage::{ParticleId, ParticleStorage, StreamsMut};
+use rand::{Rng, SeedableRng};
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::time::Duration;
+
+/// Random source used for emission
+///
+/// ChaCha8 yields the same stream on every target, so a seeded run is
+/// reproducible on native and wasm32 alike.
+pub type ParticleRng = rand_chacha::ChaCha8Rng;
+
+/// Step size used by `ParticleSystem::prewarm` without a fixed timestep
+const PREWARM_TIMESTEP: f32 = 1.0 / 60.0;
+
+/// Particles integrated per task with the `parallel` feature
+#[cfg(feature = "parallel")]
+const PARALLEL_CHUNK: usize = 4096;
+
+/// Creates a random source from an optional seed, falling back to OS entropy
+fn rng_from_seed(seed: Option<u64>) -> ParticleRng {
+    match seed {
+        Some(seed) => ParticleRng::seed_from_u64(seed),
+        None => ParticleRng::from_entropy(),
+    }
+}
 
 /// Represents a single particle in the system
 #[repr(C)]
 #[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Particle {
     /// Position in 2D space
     pub position: [f32; 2],
@@ -13,51 +51,82 @@ pub struct Particle {
     pub velocity: [f32; 2],
     /// Current lifetime (seconds remaining)
     pub life: f32,
+    /// Total lifetime in seconds
+    pub max_life: f32,
     /// Visual size (radius in pixels)
     pub size: f32,
     /// RGBA color
     pub color: [f32; 4],
     /// Mass for physics calculations
     pub mass: f32,
-    /// Padding for alignment
-    _padding: [f32; 2],
+    /// Position before the last simulation step, used for render interpolation
+    pub prev_position: [f32; 2],
+    /// Index of the emitter that spawned this particle
+    pub emitter: u32,
+    /// Number of sub-emitter generations above this particle
+    pub depth: u32,
+    /// Bits of the groups this particle belongs to, see `GroupMask`
+    pub groups: u32,
+    /// Orientation in radians
+    pub rotation: f32,
+    /// Spin in radians per second
+    pub angular_velocity: f32,
+    /// Index of the sprite animation frame to draw
+    pub frame: u32,
 }
 
 impl Particle {
+    /// Emitter index of particles that were not spawned by an emitter
+    pub const NO_EMITTER: u32 = u32::MAX;
+
     /// Creates a new particle with the given parameters
     pub fn new(position: Vec2, velocity: Vec2, life: f32, size: f32, color: [f32; 4]) -> Self {
         Self {
             position: position.into(),
             velocity: velocity.into(),
             life,
+            max_life: life,
             size,
             color,
             mass: 1.0,
-            _padding: [0.0; 2],
+            prev_position: position.into(),
+            emitter: Self::NO_EMITTER,
+            depth: 0,
+            groups: GroupMask::DEFAULT.bits(),
+            rotation: 0.0,
+            angular_velocity: 0.0,
+            frame: 0,
         }
     }
 
     /// Updates the particle state for one time step
-    pub fn update(&mut self, dt: f32, forces: &[Force]) {
-        let pos = Vec2::from(self.position);
-        let mut vel = Vec2::from(self.velocity);
-        
-        // Calculate acceleration from all forces
-        let mut acceleration = Vec2::ZERO;
-        for force in forces {
-            acceleration += force.calculate_at(pos) / self.mass;
-        }
-        
-        // Euler integration
-        vel += acceleration * dt;
-        let new_pos = pos + vel * dt;
-        
+    ///
+    /// Forces whose mask excludes the particle's groups are skipped.
+    pub fn update(&mut self, dt: f32, forces: &[Force], dynamics: &Dynamics) {
+        let mass = self.mass;
+        let groups = GroupMask(self.groups);
+        let acceleration = |pos: Vec2| {
+            dynamics.gravity
+                + forces
+                    .iter()
+                    .filter(|force| force.affects(groups))
+                    .map(|force| force.calculate_at(pos))
+                    .sum::<Vec2>()
+                    / mass
+        };
+
+        let (new_pos, vel) = dynamics
+            .integrator
+            .step(self.pos(), self.vel(), dt, acceleration);
+
         // Apply drag
-        vel *= 0.99;
-        
+        let vel = dynamics.drag.apply(vel, dt);
+
         // Update particle
+        self.prev_position = self.position;
         self.position = new_pos.into();
         self.velocity = vel.into();
+        self.rotation += self.angular_velocity * dt;
         self.life -= dt;
     }
 
@@ -66,6 +135,11 @@ impl Particle {
         self.life > 0.0
     }
 
+    /// Returns the fraction of the lifetime that has passed, from 0 to 1
+    pub fn normalized_age(&self) -> f32 {
+        normalized_age(self.life, self.max_life)
+    }
+
     /// Gets the particle position as Vec2
     pub fn pos(&self) -> Vec2 {
         Vec2::from(self.position)
@@ -75,17 +149,98 @@ impl Particle {
     pub fn vel(&self) -> Vec2 {
         Vec2::from(self.velocity)
     }
+
+    /// Blends between the previous and current position
+    pub fn interpolated_pos(&self, alpha: f32) -> Vec2 {
+        Vec2::from(self.prev_position).lerp(self.pos(), alpha)
+    }
+}
+
+/// Fraction of a lifetime that has passed, from 0 at birth to 1 at death
+fn normalized_age(life: f32, max_life: f32) -> f32 {
+    if max_life > 0.0 {
+        (1.0 - life / max_life).clamp(0.0, 1.0)
+    } else {
+        1.0
+    }
+}
+
+/// Global motion parameters applied to a particle on top of the force list
+#[derive(Clone, Copy, Debug)]
+pub struct Dynamics {
+    pub integrator: Integrator,
+    /// Acceleration applied regardless of mass
+    pub gravity: Vec2,
+    pub drag: Drag,
+}
+
+/// What happens to a new particle when there is no room for it
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum OverflowPolicy {
+    /// Drop the new particle
+    #[default]
+    Reject,
+    /// Replace the particle that has lived the longest
+    RecycleOldest,
+    /// Replace the particle closest to dying
+    RecycleShortestLife,
 }
 
 /// Configuration for particle system behavior
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
 pub struct ParticleConfig {
     pub max_particles: usize,
     pub spawn_rate: f32,
     pub particle_lifetime: f32,
     pub particle_size: f32,
     pub gravity: Vec2,
+    /// Drag strength per second, see `drag_model`
     pub drag_coefficient: f32,
+    /// Air resistance model used with `drag_coefficient`
+    pub drag_model: DragModel,
+    /// Seed for the random source; `None` draws one from the OS
+    pub seed: Option<u64>,
+    /// Step size used by `ParticleSystem::advance`; `None` steps by the frame time
+    pub fixed_timestep: Option<f32>,
+    /// Maximum number of fixed steps run per `advance` call
+    pub max_substeps: u32,
+    /// Integration scheme used to advance particles
+    pub integrator: Integrator,
+    /// Fraction of the normal speed kept when bouncing off a collider
+    pub restitution: f32,
+    /// Sub-emitter generations allowed below an emitted particle
+    pub max_sub_emitter_depth: u32,
+    /// Handling of new particles beyond `max_particles` or an emitter's budget
+    pub overflow: OverflowPolicy,
+    /// Most emitters the system holds at once, see `ParticleSystem::add_emitter`
+    pub max_emitters: usize,
+    /// Record `ParticleEvent`s during updates, see `ParticleSystem::drain_events`
+    pub record_events: bool,
+    /// Area particles are kept in; `None` lets them roam until they die
+    pub bounds: Option<WorldBounds>,
+    /// Liquid behaviour of the groups in `fluid.mask`
+    pub fluid: Fluid,
+    /// Flocks steering the groups in their masks
+    pub flocks: Vec<Flock>,
+}
+
+impl ParticleConfig {
+    /// Returns the drag described by `drag_model` and `drag_coefficient`
+    pub fn drag(&self) -> Drag {
+        Drag::new(self.drag_model, self.drag_coefficient)
+    }
+
+    /// Returns the dynamics for particles without an emitter override
+    pub fn dynamics(&self) -> Dynamics {
+        Dynamics {
+            integrator: self.integrator,
+            gravity: self.gravity,
+            drag: self.drag(),
+        }
+    }
 }
 
 impl Default for ParticleConfig {
@@ -96,14 +251,52 @@ impl Default for ParticleConfig {
             particle_lifetime: 5.0,
             particle_size: 3.0,
             gravity: Vec2::new(0.0, 100.0),
-            drag_coefficient: 0.99,
+            drag_coefficient: 0.6,
+            drag_model: DragModel::default(),
+            seed: None,
+            fixed_timestep: None,
+            max_substeps: 8,
+            integrator: Integrator::default(),
+            restitution: 0.5,
+            max_sub_emitter_depth: 3,
+            overflow: OverflowPolicy::default(),
+            max_emitters: 64,
+            record_events: false,
+            bounds: None,
+            fluid: Fluid::default(),
+            flocks: Vec::new(),
         }
     }
 }
 
-/// Particle emitter that spawns new particles
+/// Stable handle to an emitter of a `ParticleSystem`
+///
+/// Emitter indices shift when an earlier emitter is removed, handles do not.
+/// Handles are never reused, so a removed emitter's handle resolves to `None`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EmitterId(u32);
+
+impl EmitterId {
+    /// Returns the raw value of this handle, e.g. to pass it to JavaScript
+    pub fn to_bits(self) -> u32 {
+        self.0
+    }
+
+    /// Recreates a handle from the value returned by `to_bits`
+    pub fn from_bits(bits: u32) -> Self {
+        Self(bits)
+    }
+}
+
+/// Authored settings of an emitter
+///
+/// Everything a designer sets, without timers, random state or handles.
+/// Scenes describe emitters by their settings, see `Emitter::apply`.
 #[derive(Clone, Debug)]
-pub struct Emitter {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct EmitterSettings {
     pub position: Vec2,
     pub rate: f32,
     pub spread: f32,
@@ -111,43 +304,422 @@ pub struct Emitter {
     pub particle_lifetime: f32,
     pub particle_size: f32,
     pub enabled: bool,
-    accumulator: f32,
+    /// Overrides the system drag for particles from this emitter
+    pub drag: Option<Drag>,
+    /// Area or edge particles spawn from
+    pub shape: EmitterShape,
+    /// How spawn points are picked on `shape`
+    pub sampling: ShapeSampling,
+    /// Angle in radians particles are aimed at, 0 being +X
+    pub direction: f32,
+    /// Aim particles along the shape's surface normal instead of `direction`
+    pub velocity_along_normal: bool,
+    /// Fraction of the emitter's own velocity added to its particles
+    pub inherit_velocity: f32,
+    /// Groups the emitted particles belong to
+    pub groups: GroupMask,
+    /// Color multiplier over normalized particle age
+    pub color_over_life: Option<Gradient>,
+    /// Size multiplier over normalized particle age
+    pub size_over_life: Option<Curve>,
+    /// Alpha multiplier over normalized particle age
+    pub alpha_over_life: Option<Curve>,
+    /// Multiplier on how far particles move each step over normalized age
+    pub speed_over_life: Option<Curve>,
+    /// Bursts fired at set points in emitter time
+    pub bursts: Vec<Burst>,
+    /// Multiplier on `rate` over emitter time
+    pub rate_envelope: Option<RateEnvelope>,
+    /// Child bursts fired by events of this emitter's particles
+    ///
+    /// Sub-emitters refer to other emitters by their index in the system,
+    /// or in the scene.
+    pub sub_emitters: Vec<SubEmitter>,
+    /// `(min, max)` initial rotation in radians
+    pub rotation: (f32, f32),
+    /// `(min, max)` spin in radians per second
+    pub angular_velocity: (f32, f32),
+    /// Most particles this emitter may have alive at once
+    pub max_particles: Option<usize>,
+    /// Share of the system's particles relative to other emitters' weights
+    ///
+    /// When the system is full, recycling policies take particles from the
+    /// emitter furthest above its weighted share, so a new emitter gets its
+    /// share even next to long-running ones.
+    pub weight: f32,
+    /// Number of frames in the particles' sprite animation
+    pub frames: u32,
+    /// Animation speed in frames per second; `None` plays the frames once over the lifetime
+    pub frame_rate: Option<f32>,
+    /// Seconds of emission per cycle; `None` emits forever
+    pub duration: Option<f32>,
+    /// Start a new cycle after `duration` instead of finishing
+    pub looping: bool,
+    /// Seconds to wait before emitting
+    pub start_delay: f32,
+    /// Remove the emitter from its system once it has finished and its particles have died
+    pub remove_when_finished: bool,
 }
 
-impl Emitter {
-    /// Creates a new emitter at the given position
-    pub fn new(position: Vec2) -> Self {
+impl Default for EmitterSettings {
+    fn default() -> Self {
         Self {
-            position,
+            position: Vec2::ZERO,
             rate: 100.0,
             spread: std::f32::consts::PI / 4.0,
             initial_velocity: 50.0,
             particle_lifetime: 5.0,
             particle_size: 3.0,
             enabled: true,
+            drag: None,
+            shape: EmitterShape::default(),
+            sampling: ShapeSampling::default(),
+            direction: 0.0,
+            velocity_along_normal: false,
+            inherit_velocity: 0.0,
+            groups: GroupMask::DEFAULT,
+            color_over_life: None,
+            size_over_life: None,
+            alpha_over_life: None,
+            speed_over_life: None,
+            bursts: Vec::new(),
+            rate_envelope: None,
+            sub_emitters: Vec::new(),
+            rotation: (0.0, 0.0),
+            angular_velocity: (0.0, 0.0),
+            max_particles: None,
+            weight: 1.0,
+            frames: 1,
+            frame_rate: None,
+            duration: None,
+            looping: false,
+            start_delay: 0.0,
+            remove_when_finished: true,
+        }
+    }
+}
+
+/// Particle emitter that spawns new particles
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Emitter {
+    /// What and how the emitter spawns
+    pub settings: EmitterSettings,
+    accumulator: f32,
+    time: f32,
+    /// Seconds of `start_delay` waited out so far
+    delay_elapsed: f32,
+    finished: bool,
+    id: Option<EmitterId>,
+    /// Position at the previous `emit_with`, `None` before the first one
+    last_position: Option<Vec2>,
+    /// Start and end of the motion `ParticleSystem::advance` spreads over its steps
+    #[cfg_attr(feature = "serde", serde(skip))]
+    path: Option<(Vec2, Vec2)>,
+    velocity: Vec2,
+    /// Particles that could not be spawned for lack of room
+    dropped: u64,
+    sequence: u32,
+    rng: ParticleRng,
+}
+
+impl Emitter {
+    /// Creates a new emitter at the given position
+    pub fn new(position: Vec2) -> Self {
+        Self::with_settings(EmitterSettings {
+            position,
+            ..EmitterSettings::default()
+        })
+    }
+
+    /// Creates a new emitter with the given settings
+    pub fn with_settings(settings: EmitterSettings) -> Self {
+        Self {
+            settings,
             accumulator: 0.0,
+            time: 0.0,
+            delay_elapsed: 0.0,
+            finished: false,
+            id: None,
+            last_position: None,
+            path: None,
+            velocity: Vec2::ZERO,
+            dropped: 0,
+            sequence: 0,
+            rng: ParticleRng::from_entropy(),
         }
     }
 
+    /// Reseeds the emitter's random source
+    pub fn reseed(&mut self, seed: u64) {
+        self.rng = ParticleRng::seed_from_u64(seed);
+    }
+
+    /// Returns true if any appearance curve is set
+    fn has_appearance_curves(&self) -> bool {
+        self.settings.color_over_life.is_some()
+            || self.settings.size_over_life.is_some()
+            || self.settings.alpha_over_life.is_some()
+    }
+
+    /// Returns true if a sub-emitter fires on `trigger`
+    fn has_sub_emitter(&self, trigger: SubEmitterTrigger) -> bool {
+        self.settings
+            .sub_emitters
+            .iter()
+            .any(|sub| sub.trigger == trigger)
+    }
+
     /// Spawns particles for this frame, returns the particles to add
     pub fn emit(&mut self, dt: f32) -> Vec<Particle> {
-        if !self.enabled {
-            return Vec::new();
+        let mut particles = Vec::new();
+        self.emit_with(dt, |particle| particles.push(particle));
+        particles
+    }
+
+    /// Returns the seconds this emitter has been emitting for
+    ///
+    /// Starts after `start_delay` and restarts with every cycle of a looping
+    /// emitter, so bursts and the rate envelope repeat with the cycle.
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
+    /// Returns the handle given by `ParticleSystem::add_emitter`, if any
+    pub fn id(&self) -> Option<EmitterId> {
+        self.id
+    }
+
+    /// Returns true once the emitter has stopped for good
+    ///
+    /// Emitters finish at the end of a non-looping `duration` or when
+    /// stopped. Their particles live on; see `remove_when_finished`.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Stops emitting for good, letting the existing particles live out
+    pub fn stop(&mut self) {
+        self.finished = true;
+    }
+
+    /// Starts over from the start delay, firing all bursts again
+    pub fn restart(&mut self) {
+        self.accumulator = 0.0;
+        self.time = 0.0;
+        self.delay_elapsed = 0.0;
+        self.finished = false;
+        for burst in &mut self.settings.bursts {
+            burst.reset();
         }
+    }
+
+    /// Returns how many particles were dropped for lack of room
+    ///
+    /// Counts particles rejected by `OverflowPolicy::Reject` as well as
+    /// those that found nothing to recycle.
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    /// Returns the emitter's velocity over the last step
+    pub fn velocity(&self) -> Vec2 {
+        self.velocity
+    }
+
+    /// Moves the emitter without sweeping particles along the way
+    ///
+    /// Plain writes to `position` are treated as motion: the next frame
+    /// spawns along the path and particles inherit the resulting velocity.
+    pub fn teleport(&mut self, position: Vec2) {
+        self.settings.position = position;
+        self.last_position = None;
+        self.velocity = Vec2::ZERO;
+    }
+
+    /// Replaces the emitter's settings, keeping its state
+    ///
+    /// Timers, random state and handle stay as they are, so the emitter
+    /// carries on where it was. Bursts are only replaced, and fire anew, if
+    /// they changed; a moved emitter is teleported instead of gaining the
+    /// velocity of the jump.
+    pub fn apply(&mut self, settings: &EmitterSettings) {
+        let position = self.settings.position;
+        let bursts = std::mem::take(&mut self.settings.bursts);
+        let mut schedule = bursts.clone();
+        schedule.iter_mut().for_each(Burst::reset);
 
-        self.accumulator += dt * self.rate;
+        self.settings = settings.clone();
+        if schedule == settings.bursts {
+            self.settings.bursts = bursts;
+        }
+        if position != settings.position {
+            self.teleport(settings.position);
+        }
+    }
+
+    /// Remembers the motion since the last step for `follow_path`
+    fn begin_path(&mut self) {
+        let from = self.last_position.unwrap_or(self.settings.position);
+        self.path = Some((from, self.settings.position));
+    }
+
+    /// Moves the emitter to fraction `t` of the motion since the last step
+    ///
+    /// Forgets the motion once the end is reached.
+    fn follow_path(&mut self, t: f32) {
+        let Some((from, to)) = self.path else {
+            return;
+        };
+        if t >= 1.0 {
+            self.settings.position = to;
+            self.path = None;
+        } else {
+            self.settings.position = from.lerp(to, t);
+        }
+    }
+
+    /// Spawns particles for this frame, handing each one to `spawn`
+    ///
+    /// Allocation-free counterpart of `emit`. Spawns the continuous `rate`,
+    /// scaled by `rate_envelope`, plus any `bursts` that came due. Nothing
+    /// is spawned during `start_delay` or once the emitter has finished.
+    ///
+    /// Continuous spawns are spread over the frame: each one starts where
+    /// the emitter was at its spawn time, on the path from last frame's
+    /// position, and has already moved for the rest of the frame. Trails
+    /// of fast emitters stay continuous instead of clumping.
+    pub fn emit_with(&mut self, dt: f32, mut spawn: impl FnMut(Particle)) {
+        // Track motion even while disabled so re-enabling does not sweep
+        let from = self.last_position.unwrap_or(self.settings.position);
+        let to = self.settings.position;
+        self.last_position = Some(to);
+        if dt > 0.0 {
+            self.velocity = (to - from) / dt;
+        }
+
+        if !self.settings.enabled || self.finished {
+            return;
+        }
+
+        // Only the part of the frame past the start delay emits
+        let mut active = dt;
+        if self.delay_elapsed < self.settings.start_delay {
+            self.delay_elapsed += dt;
+            if self.delay_elapsed < self.settings.start_delay {
+                return;
+            }
+            active = self.delay_elapsed - self.settings.start_delay;
+        }
+
+        // A single cycle ends partway through the frame
+        if let Some(duration) = self.settings.duration.filter(|_| !self.settings.looping) {
+            let left = (duration - self.time).max(0.0);
+            if active >= left {
+                active = left;
+                self.finished = true;
+            }
+        }
+
+        let start = dt - active;
+        self.time += active;
+        let rate = match &self.settings.rate_envelope {
+            Some(envelope) => self.settings.rate * envelope.evaluate(self.time).max(0.0),
+            None => self.settings.rate,
+        };
+
+        let carried = self.accumulator;
+        self.accumulator += active * rate;
         let count = self.accumulator.floor() as usize;
         self.accumulator -= count as f32;
 
-        let mut rng = rand::thread_rng();
-        let mut particles = Vec::with_capacity(count);
+        // The n-th spawn happens when the accumulator crosses n
+        self.spawn_particles(count, &mut spawn, |n| {
+            let spawn_time = start + ((n + 1) as f32 - carried) / rate;
+            let t = (spawn_time / dt).clamp(0.0, 1.0);
+            (from.lerp(to, t), dt * (1.0 - t))
+        });
 
-        for _ in 0..count {
-            let angle = rng.gen_range(-self.spread..self.spread);
-            let velocity = Vec2::new(
-                angle.cos() * self.initial_velocity,
-                angle.sin() * self.initial_velocity,
-            );
+        let mut bursts = self.due_bursts();
+        if let Some(duration) = self
+            .settings
+            .duration
+            .filter(|&d| self.settings.looping && d > 0.0)
+        {
+            while self.time >= duration {
+                self.time -= duration;
+                for burst in &mut self.settings.bursts {
+                    burst.reset();
+                }
+                bursts += self.due_bursts();
+            }
+        }
+        self.spawn_particles(bursts as usize, &mut spawn, |_| (to, 0.0));
+    }
+
+    /// Counts the particles of bursts due up to the current emitter time
+    ///
+    /// Bursts past the end of a looping cycle never fire.
+    fn due_bursts(&mut self) -> u32 {
+        let time = match self.settings.duration {
+            Some(duration) if self.settings.looping => self.time.min(duration),
+            _ => self.time,
+        };
+        self.settings
+            .bursts
+            .iter_mut()
+            .map(|burst| burst.due(time))
+            .sum()
+    }
+
+    /// Spawns `count` particles at once, handing each one to `spawn`
+    ///
+    /// Fires even while the emitter is disabled, so a disabled emitter can
+    /// serve as a pure burst source.
+    pub fn burst_with(&mut self, count: u32, spawn: impl FnMut(Particle)) {
+        self.burst_at(self.settings.position, count, spawn);
+    }
+
+    /// Spawns `count` particles as if the emitter were at `origin`
+    pub fn burst_at(&mut self, origin: Vec2, count: u32, mut spawn: impl FnMut(Particle)) {
+        self.spawn_particles(count as usize, &mut spawn, |_| (origin, 0.0));
+    }
+
+    /// Spawns `count` particles, placing the n-th with `placement`
+    ///
+    /// `placement` returns the emitter position to spawn from and how long
+    /// the particle has already been alive by the end of the frame.
+    fn spawn_particles(
+        &mut self,
+        count: usize,
+        spawn: &mut impl FnMut(Particle),
+        placement: impl Fn(usize) -> (Vec2, f32),
+    ) {
+        let (settings, rng) = (&self.settings, &mut self.rng);
+
+        // libm keeps the trigonometry bit-identical across targets
+        let (sin, cos) = libm::sincosf(settings.direction);
+        let base_direction = Vec2::new(cos, sin);
+        let inherited = self.velocity * settings.inherit_velocity;
+
+        for n in 0..count {
+            let (origin, age) = placement(n);
+            let sample = settings
+                .shape
+                .sample(origin, settings.sampling, &mut self.sequence, rng);
+            let direction = match sample.normal {
+                Some(normal) if settings.velocity_along_normal => normal,
+                _ => base_direction,
+            };
+
+            let angle = if settings.spread > 0.0 {
+                rng.gen_range(-settings.spread..settings.spread)
+            } else {
+                0.0
+            };
+            let (sin, cos) = libm::sincosf(angle);
+            let velocity =
+                Vec2::new(cos, sin).rotate(direction) * settings.initial_velocity + inherited;
 
             let color = [
                 rng.gen_range(0.5..1.0),
@@ -156,66 +728,1314 @@ impl Emitter {
                 1.0,
             ];
 
-            particles.push(Particle::new(
-                self.position,
+            // Catch up on the part of the frame the particle was already alive for
+            let mut particle = Particle::new(
+                sample.position + velocity * age,
                 velocity,
-                self.particle_lifetime,
-                self.particle_size,
+                settings.particle_lifetime,
+                settings.particle_size,
                 color,
-            ));
+            );
+            particle.life -= age;
+            particle.groups = settings.groups.bits();
+            particle.rotation = random_in(rng, settings.rotation);
+            particle.angular_velocity = random_in(rng, settings.angular_velocity);
+            particle.rotation += particle.angular_velocity * age;
+            spawn(particle);
         }
+    }
+}
 
-        particles
+/// Draws a value from a `(min, max)` range, or `min` if the range is empty
+///
+/// Empty ranges draw nothing, so unused ranges leave the random stream as is.
+fn random_in(rng: &mut ParticleRng, (min, max): (f32, f32)) -> f32 {
+    if max > min {
+        rng.gen_range(min..max)
+    } else {
+        min
+    }
+}
+
+/// Integrator buffers reused across updates
+#[derive(Default)]
+struct StepScratch {
+    serial: IntegratorScratch,
+    /// One scratch per chunk integrated in parallel
+    #[cfg(feature = "parallel")]
+    chunks: Vec<IntegratorScratch>,
+}
+
+/// Moves particles under the forces with the configured integrator
+///
+/// Returns the time spent evaluating forces.
+#[cfg(not(feature = "parallel"))]
+fn step_particles(
+    streams: &mut StreamsMut,
+    dt: f32,
+    forces: &[Force],
+    dynamics: &Dynamics,
+    scratch: &mut StepScratch,
+) -> Duration {
+    let (masses, groups) = (streams.masses, streams.groups);
+    let mut force_time = Duration::ZERO;
+    dynamics.integrator.step_all(
+        streams.positions,
+        streams.velocities,
+        dt,
+        &mut scratch.serial,
+        |positions, accelerations| {
+            let watch = Stopwatch::start();
+            accelerate(positions, masses, groups, forces, dynamics, accelerations);
+            force_time += watch.elapsed();
+        },
+    );
+    force_time
+}
+
+/// Moves particles under the forces with the configured integrator
+///
+/// Splits the particles into chunks that are integrated on the rayon
+/// thread pool. Each particle is stepped on its own, so the results are
+/// identical to the serial path no matter how the work is split.
+///
+/// Returns the time spent evaluating forces. Threads evaluate forces at
+/// the same time, so the wall-clock time of the step is split by the share
+/// the threads spent on forces.
+#[cfg(feature = "parallel")]
+fn step_particles(
+    streams: &mut StreamsMut,
+    dt: f32,
+    forces: &[Force],
+    dynamics: &Dynamics,
+    scratch: &mut StepScratch,
+) -> Duration {
+    use rayon::prelude::*;
+
+    let (masses, groups) = (streams.masses, streams.groups);
+    let len = streams.positions.len();
+    if len <= PARALLEL_CHUNK {
+        let mut force_time = Duration::ZERO;
+        dynamics.integrator.step_all(
+            streams.positions,
+            streams.velocities,
+            dt,
+            &mut scratch.serial,
+            |positions, accelerations| {
+                let watch = Stopwatch::start();
+                accelerate(positions, masses, groups, forces, dynamics, accelerations);
+                force_time += watch.elapsed();
+            },
+        );
+        return force_time;
+    }
+
+    let chunks = len.div_ceil(PARALLEL_CHUNK);
+    if scratch.chunks.len() < chunks {
+        scratch.chunks.resize_with(chunks, Default::default);
+    }
+
+    let step = Stopwatch::start();
+    let (force_time, chunk_time) = streams
+        .positions
+        .par_chunks_mut(PARALLEL_CHUNK)
+        .zip(streams.velocities.par_chunks_mut(PARALLEL_CHUNK))
+        .zip(masses.par_chunks(PARALLEL_CHUNK))
+        .zip(groups.par_chunks(PARALLEL_CHUNK))
+        .zip(scratch.chunks.par_iter_mut())
+        .map(|((((positions, velocities), masses), groups), scratch)| {
+            let chunk = Stopwatch::start();
+            let mut force_time = Duration::ZERO;
+            dynamics.integrator.step_all(
+                positions,
+                velocities,
+                dt,
+                scratch,
+                |positions, accelerations| {
+                    let watch = Stopwatch::start();
+                    accelerate(positions, masses, groups, forces, dynamics, accelerations);
+                    force_time += watch.elapsed();
+                },
+            );
+            (force_time, chunk.elapsed())
+        })
+        .reduce(
+            || (Duration::ZERO, Duration::ZERO),
+            |a, b| (a.0 + b.0, a.1 + b.1),
+        );
+
+    if chunk_time.is_zero() {
+        return Duration::ZERO;
+    }
+    step.elapsed()
+        .mul_f64(force_time.as_secs_f64() / chunk_time.as_secs_f64())
+}
+
+/// Fills `accelerations` with the acceleration of each particle at `positions`
+///
+/// Sums the forces that affect the particle's groups in order, divides by
+/// its mass and adds gravity, matching `Particle::update`.
+fn accelerate(
+    positions: &[Vec2],
+    masses: &[f32],
+    groups: &[u32],
+    forces: &[Force],
+    dynamics: &Dynamics,
+    accelerations: &mut [Vec2],
+) {
+    accelerations.fill(Vec2::ZERO);
+    for force in forces {
+        let mask = force.mask();
+        if mask == GroupMask::ALL {
+            force.accumulate_all(positions, accelerations);
+            continue;
+        }
+
+        for ((acc, pos), &groups) in accelerations.iter_mut().zip(positions).zip(groups) {
+            if mask.intersects(GroupMask(groups)) {
+                *acc += force.calculate_at(*pos);
+            }
+        }
+    }
+    for (acc, mass) in accelerations.iter_mut().zip(masses) {
+        *acc = dynamics.gravity + *acc / *mass;
+    }
+}
+
+/// Particle event waiting to fire its emitter's sub-emitters
+#[derive(Clone, Copy, Debug)]
+struct SubEmission {
+    trigger: SubEmitterTrigger,
+    parent: Particle,
+}
+
+/// Live particle count and limits of one emitter during emission
+#[derive(Clone, Copy, Debug)]
+struct EmitterBudget {
+    count: usize,
+    limit: Option<usize>,
+    weight: f32,
+    dropped: u64,
+    id: Option<EmitterId>,
+    /// Particles spawned and recycled since the budget was refreshed
+    spawned: usize,
+    recycled: usize,
+}
+
+/// Particle the overflow policy may recycle, ordered by its recycle key
+#[derive(Clone, Copy, Debug)]
+struct Victim {
+    key: f32,
+    id: ParticleId,
+}
+
+impl PartialEq for Victim {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+
+impl Eq for Victim {}
+
+impl PartialOrd for Victim {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Victim {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key.total_cmp(&other.key)
+    }
+}
+
+/// Each emitter's particles in the order the overflow policy recycles them
+///
+/// An emitter's queue is filled on its first recycle after the budgets are
+/// refreshed, so picking victims scans the particles once per emitter and
+/// frame rather than once per spawn.
+#[derive(Debug, Default)]
+struct RecycleQueues {
+    queues: Vec<BinaryHeap<Victim>>,
+    filled: Vec<bool>,
+}
+
+impl RecycleQueues {
+    /// Forgets the queued particles so they are gathered afresh
+    fn invalidate(&mut self) {
+        self.filled.fill(false);
+    }
+
+    /// Takes the next victim of `emitter`, refilling its queue if needed
+    ///
+    /// `key` ranks particles by life and max life; higher keys come first.
+    /// A drained queue is refilled once, picking up particles spawned since
+    /// it was filled.
+    fn pop(
+        &mut self,
+        particles: &ParticleStorage,
+        emitter: usize,
+        key: fn(f32, f32) -> f32,
+    ) -> Option<ParticleId> {
+        if emitter >= self.queues.len() {
+            self.queues.resize_with(emitter + 1, BinaryHeap::new);
+            self.filled.resize(emitter + 1, false);
+        }
+
+        let mut refilled = false;
+        loop {
+            if !self.filled[emitter] {
+                self.fill(particles, emitter, key);
+                refilled = true;
+            }
+            if let Some(victim) = self.queues[emitter].pop() {
+                // Skip particles that died since the queue was filled
+                if particles.contains(victim.id) {
+                    return Some(victim.id);
+                }
+            } else if refilled {
+                return None;
+            } else {
+                self.filled[emitter] = false;
+            }
+        }
+    }
+
+    /// Queues every live particle of `emitter`
+    fn fill(&mut self, particles: &ParticleStorage, emitter: usize, key: fn(f32, f32) -> f32) {
+        let mut victims = std::mem::take(&mut self.queues[emitter]).into_vec();
+        victims.clear();
+        let (emitters, lives, max_lives) = (
+            particles.emitters(),
+            particles.lives(),
+            particles.max_lives(),
+        );
+        victims.extend(
+            (0..emitters.len())
+                .filter(|&i| emitters[i] as usize == emitter)
+                .map(|i| Victim {
+                    key: key(lives[i], max_lives[i]),
+                    id: particles.ids()[i],
+                }),
+        );
+        self.queues[emitter] = BinaryHeap::from(victims);
+        self.filled[emitter] = true;
+    }
+}
+
+/// Adds emitted particles to storage within the particle budgets
+struct Spawner<'a> {
+    particles: &'a mut ParticleStorage,
+    sub_emissions: &'a mut Vec<SubEmission>,
+    budgets: &'a mut [EmitterBudget],
+    recycle_queues: &'a mut RecycleQueues,
+    max_particles: usize,
+    overflow: OverflowPolicy,
+    /// Receives spawn and recycle events if they are recorded
+    events: Option<&'a mut Vec<ParticleEvent>>,
+}
+
+impl Spawner<'_> {
+    /// Stores the particle and queues its birth if `births` is set
+    ///
+    /// Makes room according to the overflow policy if the emitter is at its
+    /// budget or the system is full. Returns false if the particle was dropped,
+    /// including when the system is full of particles no emitter owns.
+    fn spawn(&mut self, particle: Particle, births: bool) -> bool {
+        let emitter = particle.emitter as usize;
+        let Some(budget) = self.budgets.get(emitter) else {
+            return false;
+        };
+
+        let full = self.particles.len() >= self.max_particles;
+        let victim = if budget.limit.is_some_and(|limit| budget.count >= limit) {
+            Some(emitter)
+        } else if full {
+            self.most_over_share()
+        } else {
+            None
+        };
+
+        let room = match victim {
+            Some(victim) => self.recycle(victim),
+            None => !full,
+        };
+        if !room {
+            self.budgets[emitter].dropped += 1;
+            return false;
+        }
+
+        let id = self.particles.push(particle);
+        self.budgets[emitter].count += 1;
+        self.budgets[emitter].spawned += 1;
+        if let Some(events) = &mut self.events {
+            events.push(ParticleEvent::Spawned {
+                particle: id,
+                emitter: self.budgets[emitter].id,
+                position: particle.pos(),
+            });
+        }
+        if births {
+            self.sub_emissions.push(SubEmission {
+                trigger: SubEmitterTrigger::Birth,
+                parent: particle,
+            });
+        }
+        true
+    }
+
+    /// Returns the emitter with the most particles relative to its weight
+    fn most_over_share(&self) -> Option<usize> {
+        let load = |budget: &EmitterBudget| {
+            if budget.weight > 0.0 {
+                budget.count as f32 / budget.weight
+            } else {
+                f32::INFINITY
+            }
+        };
+
+        self.budgets
+            .iter()
+            .enumerate()
+            .filter(|(_, budget)| budget.count > 0)
+            .max_by(|(_, a), (_, b)| load(a).total_cmp(&load(b)))
+            .map(|(index, _)| index)
+    }
+
+    /// Removes a particle of `emitter` chosen by the overflow policy
+    ///
+    /// Returns false if the policy rejects or the emitter has no particles.
+    fn recycle(&mut self, emitter: usize) -> bool {
+        // Higher keys are recycled first
+        let key: fn(f32, f32) -> f32 = match self.overflow {
+            OverflowPolicy::Reject => return false,
+            OverflowPolicy::RecycleOldest => |life, max_life| max_life - life,
+            OverflowPolicy::RecycleShortestLife => |life, _| -life,
+        };
+
+        let Some(id) = self.recycle_queues.pop(self.particles, emitter, key) else {
+            return false;
+        };
+
+        if let Some(particle) = self.particles.remove(id) {
+            if let Some(events) = &mut self.events {
+                events.push(ParticleEvent::Died {
+                    particle: id,
+                    emitter: self.budgets[emitter].id,
+                    position: particle.pos(),
+                });
+            }
+        }
+        self.budgets[emitter].count -= 1;
+        self.budgets[emitter].recycled += 1;
+        true
     }
 }
 
 /// Main particle system managing all particles
 pub struct ParticleSystem {
-    pub particles: Vec<Particle>,
+    pub particles: ParticleStorage,
     pub emitters: Vec<Emitter>,
     pub config: ParticleConfig,
+    /// Outlines particles bounce off
+    pub colliders: Vec<Outline>,
+    /// Outlines that report particles entering and leaving them as events
+    pub zones: Vec<Outline>,
+    rng: ParticleRng,
+    time_accumulator: f32,
+    interpolation_alpha: f32,
+    scratch: StepScratch,
+    fluid_solver: FluidSolver,
+    flock_solver: FlockSolver,
+    sub_emissions: Vec<SubEmission>,
+    budgets: Vec<EmitterBudget>,
+    recycle_queues: RecycleQueues,
+    events: Vec<ParticleEvent>,
+    stats: StepStats,
+    /// Emitter that spawns first next frame, rotated so none starves the rest
+    first_emitter: usize,
+    next_emitter_id: u32,
 }
 
 impl ParticleSystem {
     /// Creates a new particle system with default configuration
     pub fn new() -> Self {
+        Self::with_config(ParticleConfig::default())
+    }
+
+    /// Creates a new particle system with the given configuration
+    pub fn with_config(config: ParticleConfig) -> Self {
         Self {
-            particles: Vec::new(),
+            particles: ParticleStorage::with_capacity(config.max_particles),
             emitters: Vec::new(),
-            config: ParticleConfig::default(),
+            colliders: Vec::new(),
+            zones: Vec::new(),
+            rng: rng_from_seed(config.seed),
+            config,
+            time_accumulator: 0.0,
+            interpolation_alpha: 1.0,
+            scratch: StepScratch::default(),
+            fluid_solver: FluidSolver::default(),
+            flock_solver: FlockSolver::default(),
+            sub_emissions: Vec::new(),
+            budgets: Vec::new(),
+            recycle_queues: RecycleQueues::default(),
+            events: Vec::new(),
+            stats: StepStats::default(),
+            first_emitter: 0,
+            next_emitter_id: 0,
+        }
+    }
+
+    /// Reseeds the system and all of its emitters
+    ///
+    /// Emitters are reseeded in order from the system's random source, so
+    /// the same seed and emitter list always produce the same particles.
+    pub fn reseed(&mut self, seed: u64) {
+        self.config.seed = Some(seed);
+        self.rng = ParticleRng::seed_from_u64(seed);
+        for emitter in &mut self.emitters {
+            emitter.reseed(self.rng.gen());
+        }
+    }
+
+    /// Advances the simulation by one frame's worth of time
+    ///
+    /// With `config.fixed_timestep` set, the frame time is accumulated and
+    /// consumed in whole steps, at most `config.max_substeps` per call. The
+    /// leftover fraction of a step is exposed by `interpolation_alpha`.
+    /// Without it, this is a single `update` by `frame_dt`.
+    ///
+    /// Emitters moved since the last step travel evenly over the steps, so
+    /// each step sweeps its share of the path at the emitter's true speed.
+    ///
+    /// Returns the number of steps that were run.
+    pub fn advance(&mut self, frame_dt: f32, forces: &[Force]) -> u32 {
+        let step = match self.config.fixed_timestep {
+            Some(step) if step > 0.0 => step,
+            _ => {
+                self.update(frame_dt, forces);
+                self.interpolation_alpha = 1.0;
+                return 1;
+            }
+        };
+
+        self.time_accumulator += frame_dt;
+        let mut steps = 0;
+        while self.time_accumulator >= step && steps < self.config.max_substeps {
+            self.time_accumulator -= step;
+            steps += 1;
+        }
+
+        if steps > 0 {
+            for emitter in &mut self.emitters {
+                emitter.begin_path();
+            }
+        }
+        for n in 1..=steps {
+            for emitter in &mut self.emitters {
+                emitter.follow_path(n as f32 / steps as f32);
+            }
+            self.update(step, forces);
+        }
+
+        // Drop time we could not catch up on instead of spiralling
+        if self.time_accumulator >= step {
+            self.time_accumulator %= step;
         }
+
+        self.interpolation_alpha = self.time_accumulator / step;
+        steps
+    }
+
+    /// Simulates `seconds` ahead so the scene starts in its steady state
+    ///
+    /// Runs whole steps of `config.fixed_timestep`, or of 1/60 s without
+    /// one, and leaves no partial step behind for `advance` to interpolate.
+    pub fn prewarm(&mut self, seconds: f32, forces: &[Force]) {
+        let step = match self.config.fixed_timestep {
+            Some(step) if step > 0.0 => step,
+            _ => PREWARM_TIMESTEP,
+        };
+
+        for _ in 0..(seconds / step).round() as u32 {
+            self.update(step, forces);
+        }
+
+        self.time_accumulator = 0.0;
+        self.interpolation_alpha = 1.0;
+    }
+
+    /// Returns how far the simulation is between the previous and current step
+    ///
+    /// Renderers blend each particle from `prev_position` to `position` by this
+    /// amount so motion stays smooth regardless of the display refresh rate.
+    pub fn interpolation_alpha(&self) -> f32 {
+        self.interpolation_alpha
     }
 
     /// Updates all particles for one frame
+    ///
+    /// What happened during the update is available from `stats` afterwards.
     pub fn update(&mut self, dt: f32, forces: &[Force]) {
+        let update = Stopwatch::start();
+        let mut timings = PhaseTimings::default();
+
         // Update existing particles
-        for particle in &mut self.particles {
-            particle.update(dt, forces);
-        }
+        let phase = Stopwatch::start();
+        self.flow(dt);
+        self.flock(dt, forces);
+        let steering_time = phase.elapsed();
+        let phase = Stopwatch::start();
+        let force_time = self.integrate(dt, forces);
+        timings.integration = phase.elapsed().saturating_sub(force_time);
+        timings.forces = steering_time + force_time;
+        let phase = Stopwatch::start();
+        self.collide();
+        self.confine();
+        self.cross_zones();
+        timings.collision = phase.elapsed();
+        self.apply_appearance_curves();
+        self.animate_frames();
 
         // Remove dead particles
-        self.particles.retain(|p| p.is_alive());
+        self.collect_deaths();
+        let expired = self.particles.retain_alive();
 
         // Emit new particles
-        for emitter in &mut self.emitters {
-            let new_particles = emitter.emit(dt);
-            for particle in new_particles {
-                if self.particles.len() < self.config.max_particles {
-                    self.particles.push(particle);
+        let phase = Stopwatch::start();
+        self.refresh_budgets();
+        self.emit(dt);
+        self.run_sub_emitters();
+        timings.emission = phase.elapsed();
+        self.count_spawns(expired);
+        self.record_drops();
+        self.remove_finished_emitters();
+
+        self.measure();
+        timings.total = update.elapsed();
+        self.stats.timings = timings;
+    }
+
+    /// Returns the statistics of the last `update`
+    ///
+    /// `advance` may run several updates per call; only the last is kept.
+    pub fn stats(&self) -> &StepStats {
+        &self.stats
+    }
+
+    /// Accelerates fluid particles by their neighbours, see `Fluid`
+    fn flow(&mut self, dt: f32) {
+        let streams = self.particles.streams_mut();
+        self.fluid_solver.step(
+            &self.config.fluid,
+            streams.positions,
+            streams.velocities,
+            streams.masses,
+            streams.groups,
+            dt,
+        );
+    }
+
+    /// Steers flocking particles, treating colliders and repulsors as predators
+    fn flock(&mut self, dt: f32, forces: &[Force]) {
+        if self.config.flocks.is_empty() {
+            return;
+        }
+
+        let streams = self.particles.streams_mut();
+        self.flock_solver.step(
+            &self.config.flocks,
+            &self.colliders,
+            forces,
+            streams.positions,
+            streams.velocities,
+            streams.groups,
+            dt,
+        );
+    }
+
+    /// Advances positions, velocities and lifetimes, one stream at a time
+    ///
+    /// Returns the time spent evaluating forces.
+    fn integrate(&mut self, dt: f32, forces: &[Force]) -> Duration {
+        let dynamics = self.config.dynamics();
+        let mut streams = self.particles.streams_mut();
+        streams.prev_positions.copy_from_slice(streams.positions);
+
+        let force_time = step_particles(&mut streams, dt, forces, &dynamics, &mut self.scratch);
+
+        // Scale the distance moved by the speed curve of the particle's age
+        if self
+            .emitters
+            .iter()
+            .any(|emitter| emitter.settings.speed_over_life.is_some())
+        {
+            for i in 0..streams.positions.len() {
+                let Some(curve) = self
+                    .emitters
+                    .get(streams.emitters[i] as usize)
+                    .and_then(|emitter| emitter.settings.speed_over_life.as_ref())
+                else {
+                    continue;
+                };
+                let age = normalized_age(streams.lives[i], streams.max_lives[i]);
+                let step = streams.positions[i] - streams.prev_positions[i];
+                streams.positions[i] = streams.prev_positions[i] + step * curve.evaluate(age);
+            }
+        }
+
+        // Apply drag, honouring per-emitter overrides
+        if self
+            .emitters
+            .iter()
+            .all(|emitter| emitter.settings.drag.is_none())
+        {
+            dynamics.drag.apply_all(streams.velocities, dt);
+        } else {
+            for (vel, &emitter) in streams.velocities.iter_mut().zip(streams.emitters) {
+                let drag = self
+                    .emitters
+                    .get(emitter as usize)
+                    .and_then(|emitter| emitter.settings.drag)
+                    .unwrap_or(dynamics.drag);
+                *vel = drag.apply(*vel, dt);
+            }
+        }
+
+        for (rotation, angular_velocity) in
+            streams.rotations.iter_mut().zip(streams.angular_velocities)
+        {
+            *rotation += angular_velocity * dt;
+        }
+
+        for life in streams.lives.iter_mut() {
+            *life -= dt;
+        }
+        force_time
+    }
+
+    /// Pushes particles out of the colliders and reflects their velocity
+    ///
+    /// Only colliders whose mask shares a group with the particle apply.
+    fn collide(&mut self) {
+        if self.colliders.is_empty() {
+            return;
+        }
+
+        let restitution = self.config.restitution;
+        for i in 0..self.particles.len() {
+            let mut collided = false;
+            let groups = GroupMask(self.particles.groups()[i]);
+            for (collider, outline) in self.colliders.iter().enumerate() {
+                let position = self.particles.positions()[i];
+                if !outline.mask.intersects(groups) || !outline.contains(position) {
+                    continue;
+                }
+
+                let Some(surface) = outline.closest_point(position) else {
+                    continue;
+                };
+
+                // Leave through the nearest edge, bouncing relative to the outline's motion
+                let normal = (surface - position).normalize_or_zero();
+                self.particles.positions_mut()[i] = surface;
+                let velocity = &mut self.particles.velocities_mut()[i];
+                let approach = (*velocity - outline.velocity).dot(normal);
+                if approach < 0.0 {
+                    *velocity -= normal * approach * (1.0 + restitution);
+                }
+                collided = true;
+
+                if self.config.record_events {
+                    self.events.push(ParticleEvent::Collided {
+                        particle: self.particles.ids()[i],
+                        collider,
+                        position: surface,
+                        normal,
+                        speed: (-approach).max(0.0),
+                    });
                 }
             }
+
+            if collided {
+                self.queue_sub_emission(i, SubEmitterTrigger::Collision);
+            }
+        }
+    }
+
+    /// Keeps particles within `config.bounds`
+    ///
+    /// Particles at a `Kill` edge die this step and bouncing off an edge
+    /// fires collision sub-emitters, as a collider does.
+    fn confine(&mut self) {
+        let Some(bounds) = self.config.bounds else {
+            return;
+        };
+
+        for i in 0..self.particles.len() {
+            let streams = self.particles.streams_mut();
+            let crossed = bounds.confine(
+                &mut streams.positions[i],
+                &mut streams.prev_positions[i],
+                &mut streams.velocities[i],
+            );
+            match crossed {
+                Some(EdgeMode::Kill) => streams.lives[i] = 0.0,
+                Some(EdgeMode::Bounce { .. }) => {
+                    self.queue_sub_emission(i, SubEmitterTrigger::Collision)
+                }
+                _ => {}
+            }
         }
     }
 
-    /// Adds an emitter to the system
-    pub fn add_emitter(&mut self, emitter: Emitter) {
+    /// Records particles crossing a zone's edge since the previous step
+    fn cross_zones(&mut self) {
+        if !self.config.record_events || self.zones.is_empty() {
+            return;
+        }
+
+        let (ids, groups) = (self.particles.ids(), self.particles.groups());
+        let (positions, prev_positions) = (
+            self.particles.positions(),
+            self.particles.prev_positions(),
+        );
+        for (zone, outline) in self.zones.iter().enumerate() {
+            for i in 0..ids.len() {
+                if !outline.mask.intersects(GroupMask(groups[i])) {
+                    continue;
+                }
+
+                let (position, particle) = (positions[i], ids[i]);
+                let event = match (outline.contains(prev_positions[i]), outline.contains(position)) {
+                    (false, true) => ParticleEvent::Entered {
+                        particle,
+                        zone,
+                        position,
+                    },
+                    (true, false) => ParticleEvent::Left {
+                        particle,
+                        zone,
+                        position,
+                    },
+                    _ => continue,
+                };
+                self.events.push(event);
+            }
+        }
+    }
+
+    /// Queues the sub-emitters of particles whose life has run out
+    ///
+    /// Also records their deaths as events if those are recorded.
+    fn collect_deaths(&mut self) {
+        let record = self.config.record_events;
+        if !record
+            && !self
+                .emitters
+                .iter()
+                .any(|emitter| emitter.has_sub_emitter(SubEmitterTrigger::Death))
+        {
+            return;
+        }
+
+        for i in 0..self.particles.len() {
+            if self.particles.lives()[i] > 0.0 {
+                continue;
+            }
+
+            self.queue_sub_emission(i, SubEmitterTrigger::Death);
+            if record {
+                let emitter = self.particles.emitters()[i] as usize;
+                self.events.push(ParticleEvent::Died {
+                    particle: self.particles.ids()[i],
+                    emitter: self.emitters.get(emitter).and_then(Emitter::id),
+                    position: self.particles.positions()[i],
+                });
+            }
+        }
+    }
+
+    /// Queues `trigger` for the particle at `index` if its emitter reacts to it
+    ///
+    /// Children inherit the color the parent was spawned with: by the time
+    /// it dies, `alpha_over_life` has usually faded the parent out.
+    fn queue_sub_emission(&mut self, index: usize, trigger: SubEmitterTrigger) {
+        let Some(mut parent) = self.particles.get(index) else {
+            return;
+        };
+        if self
+            .emitters
+            .get(parent.emitter as usize)
+            .is_some_and(|emitter| emitter.has_sub_emitter(trigger))
+        {
+            parent.color = self.particles.base_colors()[index];
+            self.sub_emissions.push(SubEmission { trigger, parent });
+        }
+    }
+
+    /// Fires the sub-emitters of all queued events, including those of the children
+    ///
+    /// Children one generation below `config.max_sub_emitter_depth` no longer
+    /// fire sub-emitters, which keeps birth triggers from recursing forever.
+    fn run_sub_emitters(&mut self) {
+        while let Some(SubEmission { trigger, parent }) = self.sub_emissions.pop() {
+            if parent.depth >= self.config.max_sub_emitter_depth {
+                continue;
+            }
+
+            let source = parent.emitter as usize;
+            let sub_emitters = self
+                .emitters
+                .get(source)
+                .map_or(0, |emitter| emitter.settings.sub_emitters.len());
+            for k in 0..sub_emitters {
+                let sub = self.emitters[source].settings.sub_emitters[k];
+                if sub.trigger != trigger {
+                    continue;
+                }
+                if sub.probability < 1.0 && self.rng.gen::<f32>() >= sub.probability {
+                    continue;
+                }
+                let Some(child) = self.emitters.get_mut(sub.emitter) else {
+                    continue;
+                };
+
+                let births = child.has_sub_emitter(SubEmitterTrigger::Birth);
+                let mut spawner = Spawner {
+                    particles: &mut self.particles,
+                    sub_emissions: &mut self.sub_emissions,
+                    budgets: &mut self.budgets,
+                    recycle_queues: &mut self.recycle_queues,
+                    max_particles: self.config.max_particles,
+                    overflow: self.config.overflow,
+                    events: self.config.record_events.then_some(&mut self.events),
+                };
+                child.burst_at(parent.pos(), sub.count, |mut particle| {
+                    sub.inherit(&parent, &mut particle);
+                    particle.emitter = sub.emitter as u32;
+                    particle.depth = parent.depth + 1;
+                    spawner.spawn(particle, births);
+                });
+            }
+        }
+    }
+
+    /// Evaluates the emitters' color, size and alpha curves at each particle's age
+    fn apply_appearance_curves(&mut self) {
+        if !self.emitters.iter().any(Emitter::has_appearance_curves) {
+            return;
+        }
+
+        let streams = self.particles.streams_mut();
+        for i in 0..streams.lives.len() {
+            let Some(emitter) = self.emitters.get(streams.emitters[i] as usize) else {
+                continue;
+            };
+            if !emitter.has_appearance_curves() {
+                continue;
+            }
+
+            let age = normalized_age(streams.lives[i], streams.max_lives[i]);
+
+            if let Some(curve) = &emitter.settings.size_over_life {
+                streams.sizes[i] = streams.base_sizes[i] * curve.evaluate(age);
+            }
+
+            let mut color = streams.base_colors[i];
+            if let Some(gradient) = &emitter.settings.color_over_life {
+                let tint = gradient.evaluate(age);
+                for (channel, tint) in color.iter_mut().zip(tint) {
+                    *channel *= tint;
+                }
+            }
+            if let Some(curve) = &emitter.settings.alpha_over_life {
+                color[3] *= curve.evaluate(age);
+            }
+            streams.colors[i] = color;
+        }
+    }
+
+    /// Picks each particle's sprite frame from its age and its emitter's animation
+    fn animate_frames(&mut self) {
+        if self
+            .emitters
+            .iter()
+            .all(|emitter| emitter.settings.frames <= 1)
+        {
+            return;
+        }
+
+        let streams = self.particles.streams_mut();
+        for i in 0..streams.frames.len() {
+            let Some(emitter) = self.emitters.get(streams.emitters[i] as usize) else {
+                continue;
+            };
+            if emitter.settings.frames <= 1 {
+                continue;
+            }
+
+            let frame = match emitter.settings.frame_rate {
+                Some(rate) => {
+                    ((streams.max_lives[i] - streams.lives[i]) * rate) as u32
+                        % emitter.settings.frames
+                }
+                None => {
+                    let age = normalized_age(streams.lives[i], streams.max_lives[i]);
+                    ((age * emitter.settings.frames as f32) as u32).min(emitter.settings.frames - 1)
+                }
+            };
+            streams.frames[i] = frame;
+        }
+    }
+
+    /// Counts each emitter's live particles before spawning
+    fn refresh_budgets(&mut self) {
+        self.recycle_queues.invalidate();
+        self.budgets.clear();
+        self.budgets
+            .extend(self.emitters.iter().map(|emitter| EmitterBudget {
+                count: 0,
+                limit: emitter.settings.max_particles,
+                weight: emitter.settings.weight,
+                dropped: 0,
+                id: emitter.id,
+                spawned: 0,
+                recycled: 0,
+            }));
+        for &emitter in self.particles.emitters() {
+            if let Some(budget) = self.budgets.get_mut(emitter as usize) {
+                budget.count += 1;
+            }
+        }
+    }
+
+    /// Counts the particles spawned, killed and dropped during this step
+    ///
+    /// `expired` is the number of particles that ran out of life.
+    fn count_spawns(&mut self, expired: usize) {
+        let stats = &mut self.stats;
+        stats.spawned = 0;
+        stats.died = expired;
+        stats.dropped = 0;
+        for budget in &self.budgets {
+            stats.spawned += budget.spawned;
+            stats.died += budget.recycled;
+            stats.dropped += budget.dropped;
+        }
+    }
+
+    /// Records the live particles of each emitter and their motion
+    fn measure(&mut self) {
+        let stats = &mut self.stats;
+        stats.particles = self.particles.len();
+        stats.emitter_particles.clear();
+        stats
+            .emitter_particles
+            .extend(self.budgets.iter().map(|budget| budget.count));
+
+        let mut speed = 0.0;
+        let mut energy = 0.0;
+        let (velocities, masses) = (self.particles.velocities(), self.particles.masses());
+        for (velocity, &mass) in velocities.iter().zip(masses) {
+            let squared = velocity.length_squared();
+            speed += squared.sqrt();
+            energy += 0.5 * mass * squared;
+        }
+        stats.average_speed = if stats.particles > 0 {
+            speed / stats.particles as f32
+        } else {
+            0.0
+        };
+        stats.kinetic_energy = energy;
+    }
+
+    /// Adds the drops counted during spawning to the emitters
+    fn record_drops(&mut self) {
+        for (emitter, budget) in self.emitters.iter_mut().zip(&mut self.budgets) {
+            emitter.dropped += budget.dropped;
+            budget.dropped = 0;
+        }
+    }
+
+    /// Removes finished emitters whose particles have all died
+    ///
+    /// Emitters set to `remove_when_finished` are kept while another
+    /// emitter still spawns from them as a sub-emitter.
+    fn remove_finished_emitters(&mut self) {
+        for index in (0..self.emitters.len()).rev() {
+            let emitter = &self.emitters[index];
+            if !emitter.finished || !emitter.settings.remove_when_finished {
+                continue;
+            }
+            if self
+                .budgets
+                .get(index)
+                .is_some_and(|budget| budget.count > 0)
+            {
+                continue;
+            }
+            if self.emitters.iter().any(|other| {
+                other
+                    .settings
+                    .sub_emitters
+                    .iter()
+                    .any(|sub| sub.emitter == index)
+            }) {
+                continue;
+            }
+            self.remove_emitter_at(index);
+        }
+    }
+
+    /// Spawns new particles from every emitter
+    fn emit(&mut self, dt: f32) {
+        let mut spawner = Spawner {
+            particles: &mut self.particles,
+            sub_emissions: &mut self.sub_emissions,
+            budgets: &mut self.budgets,
+            recycle_queues: &mut self.recycle_queues,
+            max_particles: self.config.max_particles,
+            overflow: self.config.overflow,
+            events: self.config.record_events.then_some(&mut self.events),
+        };
+
+        let count = self.emitters.len();
+        let first = self.first_emitter % count.max(1);
+        self.first_emitter = first + 1;
+
+        for index in (first..count).chain(0..first) {
+            let emitter = &mut self.emitters[index];
+            let births = emitter.has_sub_emitter(SubEmitterTrigger::Birth);
+            emitter.emit_with(dt, |mut particle| {
+                particle.emitter = index as u32;
+                spawner.spawn(particle, births);
+            });
+        }
+    }
+
+    /// Spawns `count` particles from the emitter with the given handle right away
+    ///
+    /// Respects the particle budgets and `config.overflow`, and returns the
+    /// number spawned, not counting children of sub-emitters. Returns `None`
+    /// for a stale handle.
+    pub fn burst(&mut self, id: EmitterId, count: u32) -> Option<usize> {
+        let index = self.emitter_index(id)?;
+        self.refresh_budgets();
+        let emitter = &mut self.emitters[index];
+        let mut spawner = Spawner {
+            particles: &mut self.particles,
+            sub_emissions: &mut self.sub_emissions,
+            budgets: &mut self.budgets,
+            recycle_queues: &mut self.recycle_queues,
+            max_particles: self.config.max_particles,
+            overflow: self.config.overflow,
+            events: self.config.record_events.then_some(&mut self.events),
+        };
+        let births = emitter.has_sub_emitter(SubEmitterTrigger::Birth);
+        let mut spawned = 0;
+        emitter.burst_with(count, |mut particle| {
+            particle.emitter = index as u32;
+            if spawner.spawn(particle, births) {
+                spawned += 1;
+            }
+        });
+
+        self.run_sub_emitters();
+        self.record_drops();
+        Some(spawned)
+    }
+
+    /// Adds an emitter to the system and returns its handle
+    ///
+    /// The emitter is reseeded from the system's random source so that the
+    /// whole scene follows `config.seed`. Returns `None` without adding it
+    /// if the system already holds `config.max_emitters` emitters.
+    pub fn add_emitter(&mut self, mut emitter: Emitter) -> Option<EmitterId> {
+        if self.emitters.len() >= self.config.max_emitters {
+            return None;
+        }
+
+        let id = EmitterId(self.next_emitter_id);
+        self.next_emitter_id += 1;
+        emitter.id = Some(id);
+        emitter.reseed(self.rng.gen());
         self.emitters.push(emitter);
+        Some(id)
+    }
+
+    /// Returns the current index of the emitter with the given handle
+    pub fn emitter_index(&self, id: EmitterId) -> Option<usize> {
+        self.emitters
+            .iter()
+            .position(|emitter| emitter.id == Some(id))
+    }
+
+    /// Returns the emitter with the given handle
+    pub fn emitter(&self, id: EmitterId) -> Option<&Emitter> {
+        self.emitters.get(self.emitter_index(id)?)
+    }
+
+    /// Returns the emitter with the given handle mutably
+    pub fn emitter_mut(&mut self, id: EmitterId) -> Option<&mut Emitter> {
+        let index = self.emitter_index(id)?;
+        self.emitters.get_mut(index)
+    }
+
+    /// Stops the emitter with the given handle, letting its particles live out
+    ///
+    /// The emitter is removed once they have died unless its
+    /// `remove_when_finished` is cleared. Returns false for a stale handle.
+    pub fn stop_emitter(&mut self, id: EmitterId) -> bool {
+        self.emitter_mut(id).map(Emitter::stop).is_some()
+    }
+
+    /// Removes the emitter with the given handle along with its particles
+    ///
+    /// Later emitters move down one index; particles and sub-emitters
+    /// follow them, and sub-emitters spawning from the removed emitter are
+    /// dropped. Each removed particle is reported as `Died` while events
+    /// are recorded.
+    pub fn remove_emitter(&mut self, id: EmitterId) -> Option<Emitter> {
+        let index = self.emitter_index(id)?;
+        Some(self.remove_emitter_at(index))
+    }
+
+    /// Removes the emitter at `index`, its particles and references to it
+    pub(crate) fn remove_emitter_at(&mut self, index: usize) -> Emitter {
+        let removed = self.emitters.remove(index);
+        if index < self.budgets.len() {
+            self.budgets.remove(index);
+        }
+
+        // Removal moves the last particle into place, which was already visited
+        for i in (0..self.particles.len()).rev() {
+            let emitter = self.particles.emitters()[i];
+            if emitter == Particle::NO_EMITTER || (emitter as usize) < index {
+                continue;
+            }
+            if emitter as usize == index {
+                if let Some(id) = self.particles.id(i) {
+                    if self.config.record_events {
+                        self.events.push(ParticleEvent::Died {
+                            particle: id,
+                            emitter: removed.id(),
+                            position: self.particles.positions()[i],
+                        });
+                    }
+                    self.particles.remove(id);
+                }
+            } else {
+                self.particles.emitters_mut()[i] -= 1;
+            }
+        }
+
+        for emitter in &mut self.emitters {
+            emitter
+                .settings
+                .sub_emitters
+                .retain(|sub| sub.emitter != index);
+            for sub in &mut emitter.settings.sub_emitters {
+                if sub.emitter > index {
+                    sub.emitter -= 1;
+                }
+            }
+        }
+
+        removed
+    }
+
+    /// Returns a copy of the particle with the given handle, if it is alive
+    pub fn particle(&self, id: ParticleId) -> Option<Particle> {
+        self.particles.get_by_id(id)
+    }
+
+    /// Removes the particle with the given handle
+    pub fn kill(&mut self, id: ParticleId) -> Option<Particle> {
+        self.particles.remove(id)
     }
 
     /// Returns the current particle count
     pub fn particle_count(&self) -> usize {
         self.particles.len()
     }
+
+    /// Returns the events recorded since they were last drained
+    pub fn events(&self) -> &[ParticleEvent] {
+        &self.events
+    }
+
+    /// Removes and returns the recorded events, oldest first
+    ///
+    /// Events pile up across updates until drained, so callers recording
+    /// them should drain after every `update` or `advance`.
+    pub fn drain_events(&mut self) -> std::vec::Drain<'_, ParticleEvent> {
+        self.events.drain(..)
+    }
+
+    /// Captures the full simulation state along with the forces in use
+    ///
+    /// The snapshot includes emitter timers and every random source, so
+    /// restoring it and stepping with the same frame times and forces
+    /// continues the run exactly.
+    #[cfg(feature = "serde")]
+    pub fn snapshot(&self, forces: &[Force]) -> Snapshot {
+        Snapshot {
+            version: SNAPSHOT_VERSION,
+            config: self.config.clone(),
+            particles: self.particles.clone(),
+            emitters: self.emitters.clone(),
+            burst_cycles: self
+                .emitters
+                .iter()
+                .map(|emitter| {
+                    emitter
+                        .settings
+                        .bursts
+                        .iter()
+                        .map(|burst| burst.fired)
+                        .collect()
+                })
+                .collect(),
+            colliders: self.colliders.clone(),
+            zones: self.zones.clone(),
+            forces: forces.to_vec(),
+            rng: self.rng.clone(),
+            time_accumulator: self.time_accumulator,
+            interpolation_alpha: self.interpolation_alpha,
+            first_emitter: self.first_emitter,
+            next_emitter_id: self.next_emitter_id,
+        }
+    }
+
+    /// Replaces the simulation state with a snapshot and returns its forces
+    ///
+    /// The snapshot is checked first; on error the system is left as it was.
+    #[cfg(feature = "serde")]
+    pub fn restore(&mut self, snapshot: Snapshot) -> Result<Vec<Force>, SnapshotError> {
+        snapshot.validate()?;
+
+        self.config = snapshot.config;
+        self.particles = snapshot.particles;
+        self.emitters = snapshot.emitters;
+        for (emitter, cycles) in self.emitters.iter_mut().zip(&snapshot.burst_cycles) {
+            for (burst, &fired) in emitter.settings.bursts.iter_mut().zip(cycles) {
+                burst.fired = fired;
+            }
+        }
+        self.colliders = snapshot.colliders;
+        self.zones = snapshot.zones;
+        self.rng = snapshot.rng;
+        self.time_accumulator = snapshot.time_accumulator;
+        self.interpolation_alpha = snapshot.interpolation_alpha;
+        self.first_emitter = snapshot.first_emitter;
+        self.next_emitter_id = snapshot.next_emitter_id;
+        self.sub_emissions.clear();
+        self.events.clear();
+        self.refresh_budgets();
+        Ok(snapshot.forces)
+    }
 }
 
 impl Default for ParticleSystem {
@@ -224,3 +2044,496 @@ impl Default for ParticleSystem {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// System that recycles its oldest particles to stay within `max_particles`
+    fn recycling_system(max_particles: usize) -> (ParticleSystem, EmitterId) {
+        let mut system = ParticleSystem::with_config(ParticleConfig {
+            seed: Some(1),
+            max_particles,
+            overflow: OverflowPolicy::RecycleOldest,
+            ..ParticleConfig::default()
+        });
+        let mut emitter = Emitter::new(Vec2::ZERO);
+        emitter.settings.rate = 0.0;
+        let id = system.add_emitter(emitter).unwrap();
+        (system, id)
+    }
+
+    #[test]
+    fn recycling_keeps_the_newest_particles() {
+        let (mut system, id) = recycling_system(10);
+        assert_eq!(system.burst(id, 10), Some(10));
+        system.update(0.1, &[]);
+
+        // Recycles the aged particles first, then those of this burst
+        assert_eq!(system.burst(id, 25), Some(25));
+        assert_eq!(system.particle_count(), 10);
+        let particles = &system.particles;
+        assert!(particles.lives().iter().eq(particles.max_lives()));
+    }
+
+    #[test]
+    fn full_system_without_emitter_particles_drops_spawns() {
+        let (mut system, id) = recycling_system(100);
+        for _ in 0..100 {
+            system
+                .particles
+                .push(Particle::new(Vec2::ZERO, Vec2::ZERO, 1.0, 1.0, [1.0; 4]));
+        }
+        assert_eq!(system.burst(id, 5), Some(0));
+        assert_eq!(system.particle_count(), 100);
+
+        let (mut empty, id) = recycling_system(0);
+        assert_eq!(empty.burst(id, 5), Some(0));
+        assert_eq!(empty.particle_count(), 0);
+    }
+
+    #[test]
+    fn bursts_from_removed_emitters_are_rejected() {
+        let (mut system, id) = recycling_system(10);
+        let other = system.add_emitter(Emitter::new(Vec2::ZERO)).unwrap();
+        system.remove_emitter(id);
+
+        // The surviving emitter moved to index 0, but the stale handle stays stale
+        assert_eq!(system.burst(id, 5), None);
+        assert_eq!(system.particle_count(), 0);
+        assert_eq!(system.burst(other, 5), Some(5));
+    }
+
+    #[test]
+    fn removed_emitters_report_their_particles_as_died() {
+        let (mut system, id) = recycling_system(10);
+        system.config.record_events = true;
+        system.burst(id, 3);
+        let spawned: Vec<_> = system.drain_events().map(|e| e.particle()).collect();
+
+        system.remove_emitter(id);
+        let died: Vec<_> = system
+            .drain_events()
+            .map(|event| match event {
+                ParticleEvent::Died {
+                    particle, emitter, ..
+                } => {
+                    assert_eq!(emitter, Some(id));
+                    particle
+                }
+                other => panic!("unexpected event {:?}", other),
+            })
+            .collect();
+        assert_eq!(died.len(), 3);
+        assert!(spawned.iter().all(|particle| died.contains(particle)));
+    }
+
+    #[test]
+    fn death_children_of_faded_parents_are_visible() {
+        let mut system = ParticleSystem::with_config(ParticleConfig {
+            seed: Some(3),
+            ..ParticleConfig::default()
+        });
+        let mut parent = Emitter::new(Vec2::ZERO);
+        parent.settings.rate = 0.0;
+        parent.settings.particle_lifetime = 0.5;
+        parent.settings.alpha_over_life = Some(Curve::linear(1.0, 0.0));
+        parent.settings.sub_emitters = vec![SubEmitter::new(SubEmitterTrigger::Death, 1, 5)];
+        let mut child = Emitter::new(Vec2::ZERO);
+        child.settings.enabled = false;
+        let parent = system.add_emitter(parent).unwrap();
+        system.add_emitter(child);
+
+        assert_eq!(system.burst(parent, 1), Some(1));
+        for _ in 0..10 {
+            system.update(0.1, &[]);
+        }
+
+        let children: Vec<_> = system
+            .particles
+            .iter()
+            .filter(|particle| particle.emitter == 1)
+            .collect();
+        assert_eq!(children.len(), 5);
+        assert!(children.iter().all(|child| child.color[3] == 1.0));
+    }
+
+    #[test]
+    fn moving_emitters_spread_their_motion_over_substeps() {
+        let mut system = ParticleSystem::with_config(ParticleConfig {
+            seed: Some(5),
+            gravity: Vec2::ZERO,
+            fixed_timestep: Some(0.125),
+            ..ParticleConfig::default()
+        });
+        let mut emitter = Emitter::new(Vec2::ZERO);
+        emitter.settings.rate = 80.0;
+        emitter.settings.spread = 0.0;
+        emitter.settings.initial_velocity = 0.0;
+        emitter.settings.enabled = false;
+        let id = system.add_emitter(emitter).unwrap();
+        system.advance(0.25, &[]);
+
+        // 10 pixels in 0.25 seconds, over two steps
+        let emitter = system.emitter_mut(id).unwrap();
+        emitter.settings.enabled = true;
+        emitter.settings.position = Vec2::new(10.0, 0.0);
+        assert_eq!(system.advance(0.25, &[]), 2);
+        assert_eq!(system.emitter(id).unwrap().velocity(), Vec2::new(40.0, 0.0));
+        assert_eq!(
+            system.emitter(id).unwrap().settings.position,
+            Vec2::new(10.0, 0.0)
+        );
+
+        // Spawns are spaced evenly along the whole path
+        let mut xs: Vec<f32> = system.particles.positions().iter().map(|p| p.x).collect();
+        xs.sort_by(f32::total_cmp);
+        assert_eq!(xs.len(), 20);
+        for pair in xs.windows(2) {
+            assert!((pair[1] - pair[0] - 0.5).abs() < 1e-4, "{:?}", xs);
+        }
+    }
+
+    /// Emitter spawning long-lived particles far faster than a budget of 1000 allows
+    fn flood(x: f32) -> Emitter {
+        let mut emitter = Emitter::new(Vec2::new(x, 0.0));
+        emitter.settings.rate = 6000.0;
+        emitter.settings.particle_lifetime = 60.0;
+        emitter
+    }
+
+    fn budget_system(overflow: OverflowPolicy) -> ParticleSystem {
+        ParticleSystem::with_config(ParticleConfig {
+            max_particles: 1000,
+            seed: Some(11),
+            overflow,
+            ..ParticleConfig::default()
+        })
+    }
+
+    fn count_from(system: &ParticleSystem, emitter: u32) -> usize {
+        system
+            .particles
+            .emitters()
+            .iter()
+            .filter(|&&e| e == emitter)
+            .count()
+    }
+
+    fn run(system: &mut ParticleSystem, steps: usize) {
+        for _ in 0..steps {
+            system.update(1.0 / 60.0, &[]);
+        }
+    }
+
+    #[test]
+    fn new_emitters_take_their_share_by_recycling_the_oldest() {
+        let mut system = budget_system(OverflowPolicy::RecycleOldest);
+        system.add_emitter(flood(0.0));
+        run(&mut system, 60);
+        assert_eq!(count_from(&system, 0), 1000);
+
+        system.add_emitter(flood(100.0));
+        run(&mut system, 60);
+        let (first, second) = (count_from(&system, 0), count_from(&system, 1));
+        assert_eq!(first + second, 1000);
+        assert!(first.abs_diff(second) <= 1, "{} / {}", first, second);
+        assert_eq!(system.emitters[1].dropped(), 0);
+
+        // Particles spawned in the last step survived the recycling
+        let youngest_age = system
+            .particles
+            .lives()
+            .iter()
+            .map(|life| 60.0 - life)
+            .fold(f32::MAX, f32::min);
+        assert!(youngest_age < 1.0 / 60.0);
+
+        // Weights skew the shares
+        system.emitters[1].settings.weight = 3.0;
+        run(&mut system, 60);
+        assert!(count_from(&system, 0).abs_diff(250) <= 1);
+    }
+
+    #[test]
+    fn rejecting_keeps_the_oldest_and_counts_drops() {
+        let mut system = budget_system(OverflowPolicy::Reject);
+        system.add_emitter(flood(0.0));
+        run(&mut system, 60);
+        system.add_emitter(flood(100.0));
+        run(&mut system, 1);
+
+        assert_eq!(count_from(&system, 1), 0);
+        assert_eq!(system.emitters[1].dropped(), 100);
+        assert!(system.emitters[0].dropped() > 0);
+    }
+
+    #[test]
+    fn emitter_budgets_hold_regardless_of_free_room() {
+        for overflow in [OverflowPolicy::Reject, OverflowPolicy::RecycleShortestLife] {
+            let mut system = budget_system(overflow);
+            let mut emitter = flood(0.0);
+            emitter.settings.max_particles = Some(100);
+            system.add_emitter(emitter);
+            run(&mut system, 30);
+
+            assert_eq!(system.particle_count(), 100);
+            assert_eq!(
+                system.emitters[0].dropped() > 0,
+                overflow == OverflowPolicy::Reject
+            );
+        }
+    }
+
+    fn lifecycle_emitter(rate: f32, lifetime: f32) -> Emitter {
+        let mut emitter = Emitter::new(Vec2::ZERO);
+        emitter.settings.rate = rate;
+        emitter.settings.particle_lifetime = lifetime;
+        emitter
+    }
+
+    #[test]
+    fn timed_emitters_wait_spawn_and_go_away_after_their_particles() {
+        let mut system = ParticleSystem::with_config(ParticleConfig {
+            seed: Some(5),
+            ..ParticleConfig::default()
+        });
+        let mut timed = lifecycle_emitter(120.0, 2.0);
+        timed.settings.start_delay = 0.25;
+        timed.settings.duration = Some(1.0);
+        let id = system.add_emitter(timed).unwrap();
+
+        run(&mut system, 15);
+        assert_eq!(system.particle_count(), 0, "spawned during the delay");
+        while !system.emitter(id).unwrap().is_finished() {
+            system.update(1.0 / 60.0, &[]);
+        }
+        assert!(system.particle_count().abs_diff(120) <= 1);
+
+        // Kept until the last particle has died
+        let mut frames = 0;
+        while system.emitter(id).is_some() {
+            assert!(system.particle_count() > 0, "removed with particles left");
+            system.update(1.0 / 60.0, &[]);
+            frames += 1;
+        }
+        assert!(
+            frames > 60 && frames <= 121,
+            "removed after {} frames",
+            frames
+        );
+        assert_eq!(system.particle_count(), 0);
+    }
+
+    #[test]
+    fn looping_emitters_fire_their_bursts_every_cycle() {
+        let mut system = ParticleSystem::new();
+        let mut looping = lifecycle_emitter(0.0, 10.0);
+        looping.settings.duration = Some(0.5);
+        looping.settings.looping = true;
+        looping.settings.bursts.push(Burst::new(0.0, 10));
+        let id = system.add_emitter(looping).unwrap();
+
+        run(&mut system, 110);
+        assert_eq!(system.particle_count(), 40);
+        assert!(!system.emitter(id).unwrap().is_finished());
+    }
+
+    #[test]
+    fn emitter_handles_survive_removal_of_earlier_emitters() {
+        let mut system = ParticleSystem::with_config(ParticleConfig {
+            max_emitters: 3,
+            ..ParticleConfig::default()
+        });
+        let first = system.add_emitter(lifecycle_emitter(60.0, 10.0)).unwrap();
+        let second = system.add_emitter(lifecycle_emitter(60.0, 10.0)).unwrap();
+        let third = system.add_emitter(lifecycle_emitter(60.0, 10.0)).unwrap();
+        assert!(system.add_emitter(lifecycle_emitter(60.0, 10.0)).is_none());
+
+        run(&mut system, 1);
+        assert!(system.remove_emitter(first).is_some());
+        assert!(system.remove_emitter(first).is_none());
+        assert_eq!(system.emitter_index(second), Some(0));
+        assert_eq!(system.emitter_index(third), Some(1));
+        assert!(system.particles.emitters().iter().all(|&e| e < 2));
+        assert_eq!(system.particle_count(), 2);
+
+        // Stopped emitters let their particles live out first
+        assert!(system.stop_emitter(second));
+        run(&mut system, 1);
+        assert!(system.emitter(second).is_some());
+        assert_eq!(system.particle_count(), 3);
+    }
+
+    #[test]
+    fn prewarm_starts_in_the_steady_state() {
+        let mut cold = ParticleSystem::new();
+        let mut warm = ParticleSystem::new();
+        for system in [&mut cold, &mut warm] {
+            system.reseed(8);
+            system.add_emitter(lifecycle_emitter(100.0, 2.0));
+        }
+        warm.prewarm(3.0, &[]);
+        run(&mut cold, 1);
+        run(&mut warm, 1);
+
+        assert!(cold.particle_count() < 5);
+        assert!(warm.particle_count().abs_diff(200) <= 2);
+    }
+
+    /// System stepping in quarter seconds with one long-lived particle
+    fn fixed_step_system(max_substeps: u32) -> ParticleSystem {
+        let mut system = ParticleSystem::with_config(ParticleConfig {
+            fixed_timestep: Some(0.25),
+            max_substeps,
+            ..ParticleConfig::default()
+        });
+        system
+            .particles
+            .push(Particle::new(Vec2::ZERO, Vec2::ZERO, 100.0, 1.0, [1.0; 4]));
+        system
+    }
+
+    #[test]
+    fn advance_runs_whole_fixed_steps() {
+        let mut system = fixed_step_system(8);
+        assert_eq!(system.advance(0.625, &[]), 2);
+        assert!((system.interpolation_alpha() - 0.5).abs() < 1e-5);
+        assert_eq!(system.particles.lives()[0], 99.5);
+
+        // The leftover half step completes with the next frame
+        assert_eq!(system.advance(0.125, &[]), 1);
+        assert!(system.interpolation_alpha().abs() < 1e-5);
+        assert_eq!(system.advance(0.125, &[]), 0);
+        assert!((system.interpolation_alpha() - 0.5).abs() < 1e-5);
+        assert_eq!(system.particles.lives()[0], 99.25);
+    }
+
+    #[test]
+    fn advance_drops_time_beyond_max_substeps() {
+        let mut system = fixed_step_system(3);
+        assert_eq!(system.advance(2.125, &[]), 3);
+        assert_eq!(system.particles.lives()[0], 99.25);
+
+        // Only the fraction of a step is kept, not the five skipped steps
+        assert!((system.interpolation_alpha() - 0.5).abs() < 1e-5);
+        assert_eq!(system.advance(0.125, &[]), 1);
+        assert_eq!(system.advance(0.0, &[]), 0);
+    }
+
+    #[test]
+    fn advance_without_fixed_timestep_updates_once() {
+        let mut system = fixed_step_system(8);
+        system.config.fixed_timestep = None;
+        assert_eq!(system.advance(0.625, &[]), 1);
+        assert_eq!(system.interpolation_alpha(), 1.0);
+        assert_eq!(system.particles.lives()[0], 99.375);
+    }
+
+    /// Runs one second of a scene with an emitter at each of `positions`
+    fn seeded_run(seed: u64, positions: &[Vec2], reseed: Option<u64>) -> ParticleSystem {
+        let mut system = ParticleSystem::with_config(ParticleConfig {
+            seed: Some(seed),
+            ..ParticleConfig::default()
+        });
+        for &position in positions {
+            let mut emitter = Emitter::new(position);
+            emitter.settings.spread = std::f32::consts::PI;
+            system.add_emitter(emitter);
+        }
+        if let Some(seed) = reseed {
+            system.reseed(seed);
+        }
+        for _ in 0..60 {
+            system.update(1.0 / 60.0, &[]);
+        }
+        system
+    }
+
+    /// Returns the per-particle state that depends on the random source
+    fn random_state(system: &ParticleSystem) -> (Vec<Vec2>, Vec<Vec2>, Vec<f32>) {
+        let particles = &system.particles;
+        (
+            particles.positions().to_vec(),
+            particles.velocities().to_vec(),
+            particles.max_lives().to_vec(),
+        )
+    }
+
+    #[test]
+    fn seeded_systems_are_deterministic() {
+        let emitters = [Vec2::ZERO, Vec2::new(100.0, 0.0)];
+        let reference = random_state(&seeded_run(1, &emitters, None));
+        assert!(!reference.0.is_empty());
+        assert_eq!(random_state(&seeded_run(1, &emitters, None)), reference);
+        assert_ne!(random_state(&seeded_run(2, &emitters, None)), reference);
+
+        // Reseeding replaces the seed the emitters were added with
+        assert_eq!(
+            random_state(&seeded_run(2, &emitters, Some(1))),
+            random_state(&seeded_run(1, &emitters, None))
+        );
+        assert_ne!(random_state(&seeded_run(1, &emitters, Some(2))), reference);
+    }
+
+    #[test]
+    fn emitters_are_seeded_in_the_order_they_are_added() {
+        let (a, b) = (Vec2::ZERO, Vec2::new(100.0, 0.0));
+        let forward = seeded_run(1, &[a, b], None);
+        let backward = seeded_run(1, &[b, a], None);
+
+        // The emitter at `a` draws the second seed when added second
+        let velocities_from = |system: &ParticleSystem, emitter: u32| -> Vec<Vec2> {
+            let particles = &system.particles;
+            (0..particles.len())
+                .filter(|&i| particles.emitters()[i] == emitter)
+                .map(|i| particles.velocities()[i])
+                .collect()
+        };
+        assert_ne!(velocities_from(&forward, 0), velocities_from(&backward, 1));
+        assert_eq!(
+            velocities_from(&forward, 0),
+            velocities_from(&seeded_run(1, &[a, b], None), 0)
+        );
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_update_matches_particle_update() {
+        let count = 3 * PARALLEL_CHUNK + 17;
+        let mut system = ParticleSystem::with_config(ParticleConfig {
+            seed: Some(9),
+            max_particles: count,
+            integrator: Integrator::Rk4,
+            ..ParticleConfig::default()
+        });
+        let mut emitter = Emitter::new(Vec2::new(640.0, 360.0));
+        emitter.settings.rate = 0.0;
+        emitter.settings.spread = std::f32::consts::PI;
+        emitter.settings.initial_velocity = 200.0;
+        emitter.settings.particle_lifetime = 100.0;
+        emitter.settings.groups = GroupMask::group(0) | GroupMask::group(1);
+        let id = system.add_emitter(emitter).unwrap();
+        assert_eq!(system.burst(id, count as u32), Some(count));
+
+        let forces = [
+            Force::attractor(Vec2::new(640.0, 360.0), 5.0e5, 800.0),
+            Force::Wind {
+                direction: Vec2::new(1.0, 0.0),
+                strength: 20.0,
+                turbulence: 5.0,
+            },
+            Force::repulsor(Vec2::new(300.0, 300.0), 1.0e5, 200.0).masked(GroupMask::group(1)),
+        ];
+        let dynamics = system.config.dynamics();
+        let mut reference: Vec<_> = system.particles.iter().collect();
+        for particle in &mut reference {
+            particle.update(1.0 / 60.0, &forces, &dynamics);
+        }
+
+        system.update(1.0 / 60.0, &forces);
+        for (particle, expected) in system.particles.iter().zip(&reference) {
+            assert_eq!(particle.position, expected.position);
+            assert_eq!(particle.velocity, expected.velocity);
+        }
+    }
+}
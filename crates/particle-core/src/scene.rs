@@ -0,0 +1,352 @@
+//! Declarative JSON scenes describing config, emitters and forces
+//!
+//! A scene is the authored look of a particle system, without any runtime
+//! state. Every field except `version` may be left out and takes its
+//! default, so a scene only needs to spell out what it changes:
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "config": { "gravity": [0.0, 50.0], "fixed_timestep": 0.016666668 },
+//!   "emitters": [
+//!     { "position": [640.0, 360.0], "rate": 200.0, "alpha_over_life": [[0.0, 1.0], [1.0, 0.0]] }
+//!   ],
+//!   "forces": [
+//!     { "Attractor": { "position": [640.0, 360.0], "strength": 50000.0, "radius": 300.0 } }
+//!   ]
+//! }
+//! ```
+//!
+//! Vectors are `[x, y]` arrays, curves and gradients are lists of
+//! `[time, value]` keys and forces are tagged with their variant name.
+//! Unknown fields are rejected so typos do not go unnoticed.
+
+use crate::emission::Burst;
+use crate::particles::{Emitter, EmitterSettings, ParticleConfig, ParticleSystem};
+use crate::physics::Force;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// Scene format written by this version of the crate
+pub const SCENE_VERSION: u32 = 1;
+
+/// Config, emitters and forces of a particle system as authored
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scene {
+    /// Format version, checked by `Scene::from_json`
+    pub version: u32,
+    #[serde(default)]
+    pub config: ParticleConfig,
+    /// Settings of each emitter, added in order
+    #[serde(default)]
+    pub emitters: Vec<EmitterSettings>,
+    /// Forces passed to every update
+    #[serde(default)]
+    pub forces: Vec<Force>,
+}
+
+/// Reason a scene could not be loaded
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SceneError {
+    /// Malformed JSON or a field of the wrong type, at a 1-based position
+    Syntax {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    /// Written for an incompatible version of the format
+    UnsupportedVersion(u32),
+    /// More emitters than `config.max_emitters` allows
+    TooManyEmitters { count: usize, max: usize },
+    /// A sub-emitter refers to an emitter index past the end of the list
+    MissingEmitter(usize),
+    /// The scene file could not be read
+    Io(String),
+}
+
+impl SceneError {
+    fn syntax(error: serde_json::Error) -> Self {
+        // Drop the position serde_json appends, it is reported separately
+        let suffix = format!(" at line {} column {}", error.line(), error.column());
+        let message = error.to_string();
+        Self::Syntax {
+            line: error.line(),
+            column: error.column(),
+            message: message
+                .strip_suffix(&suffix)
+                .unwrap_or(&message)
+                .to_string(),
+        }
+    }
+}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Syntax {
+                line,
+                column,
+                message,
+            } => write!(f, "line {}, column {}: {}", line, column, message),
+            Self::UnsupportedVersion(version) => write!(
+                f,
+                "unsupported scene version {} (expected {})",
+                version, SCENE_VERSION
+            ),
+            Self::TooManyEmitters { count, max } => {
+                write!(f, "scene has {} emitters, at most {} allowed", count, max)
+            }
+            Self::MissingEmitter(index) => {
+                write!(f, "sub-emitter refers to missing emitter {}", index)
+            }
+            Self::Io(message) => write!(f, "cannot read scene: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for SceneError {}
+
+/// Leading part of a scene, read first to check the version
+#[derive(Deserialize)]
+struct Header {
+    version: u32,
+}
+
+impl Scene {
+    /// Parses and checks a scene written as JSON
+    pub fn from_json(source: &str) -> Result<Self, SceneError> {
+        let header: Header = serde_json::from_str(source).map_err(SceneError::syntax)?;
+        if header.version != SCENE_VERSION {
+            return Err(SceneError::UnsupportedVersion(header.version));
+        }
+
+        let scene: Scene = serde_json::from_str(source).map_err(SceneError::syntax)?;
+        scene.validate()?;
+        Ok(scene)
+    }
+
+    /// Reads, parses and checks a JSON scene file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, SceneError> {
+        let source = fs::read_to_string(path).map_err(|e| SceneError::Io(e.to_string()))?;
+        Self::from_json(&source)
+    }
+
+    /// Writes the scene as indented JSON
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("scenes serialize to JSON")
+    }
+
+    /// Checks that the scene can be built as written
+    pub fn validate(&self) -> Result<(), SceneError> {
+        let count = self.emitters.len();
+        if count > self.config.max_emitters {
+            return Err(SceneError::TooManyEmitters {
+                count,
+                max: self.config.max_emitters,
+            });
+        }
+
+        match self
+            .emitters
+            .iter()
+            .flat_map(|emitter| &emitter.sub_emitters)
+            .find(|sub| sub.emitter >= count)
+        {
+            Some(sub) => Err(SceneError::MissingEmitter(sub.emitter)),
+            None => Ok(()),
+        }
+    }
+
+    /// Creates a particle system from the scene and returns it with its forces
+    ///
+    /// Emitters are added in order, so a seeded scene always plays the same.
+    pub fn build(&self) -> (ParticleSystem, Vec<Force>) {
+        let mut system = ParticleSystem::with_config(self.config.clone());
+        for settings in &self.emitters {
+            system.add_emitter(Emitter::with_settings(settings.clone()));
+        }
+        (system, self.forces.clone())
+    }
+}
+
+impl ParticleSystem {
+    /// Describes the system's config and emitters as a scene
+    ///
+    /// Only authored settings are exported; particles and emitter timers
+    /// are left out, see `snapshot` to save those.
+    pub fn to_scene(&self, forces: &[Force]) -> Scene {
+        Scene {
+            version: SCENE_VERSION,
+            config: self.config.clone(),
+            emitters: self
+                .emitters
+                .iter()
+                .map(|emitter| {
+                    let mut settings = emitter.settings.clone();
+                    settings.bursts.iter_mut().for_each(Burst::reset);
+                    settings
+                })
+                .collect(),
+            forces: forces.to_vec(),
+        }
+    }
+
+    /// Switches to a scene in place and returns its forces
+    ///
+    /// Emitters are matched to the scene's by index: matching ones take the
+    /// new settings but keep their timers and particles, missing ones are
+    /// added and extra ones are removed along with their particles. All
+    /// other particles carry on, so a tweaked scene can be applied live.
+    ///
+    /// A changed `config.seed` reseeds the system before new emitters are
+    /// added, so applying a seeded scene to a fresh system matches `build`.
+    pub fn apply_scene(&mut self, scene: &Scene) -> Vec<Force> {
+        let reseed = scene
+            .config
+            .seed
+            .filter(|&seed| self.config.seed != Some(seed));
+        self.config = scene.config.clone();
+
+        while self.emitters.len() > scene.emitters.len() {
+            self.remove_emitter_at(self.emitters.len() - 1);
+        }
+        for (emitter, settings) in self.emitters.iter_mut().zip(&scene.emitters) {
+            emitter.apply(settings);
+        }
+        if let Some(seed) = reseed {
+            self.reseed(seed);
+        }
+        for settings in &scene.emitters[self.emitters.len()..] {
+            self.add_emitter(Emitter::with_settings(settings.clone()));
+        }
+
+        scene.forces.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::particles::OverflowPolicy;
+    use crate::physics::Vec2;
+
+    /// Scene the web app starts with
+    const DEFAULT_SCENE: &str = include_str!("../../../scenes/default.json");
+
+    /// Fountain whose drops burst into sparks, pulled towards the middle
+    const FOUNTAIN: &str = include_str!("../../../scenes/fountain.json");
+
+    fn run(system: &mut ParticleSystem, forces: &[Force], steps: usize) {
+        for _ in 0..steps {
+            system.update(1.0 / 60.0, forces);
+        }
+    }
+
+    /// Asserts that two systems hold the same particles in the same order
+    fn assert_same_particles(a: &ParticleSystem, b: &ParticleSystem) {
+        assert_eq!(a.particle_count(), b.particle_count());
+        for (a, b) in a.particles.iter().zip(b.particles.iter()) {
+            assert_eq!(a.position, b.position);
+            assert_eq!(a.color, b.color);
+        }
+    }
+
+    #[test]
+    fn default_scene_sets_only_what_differs() {
+        let (system, forces) = Scene::from_json(DEFAULT_SCENE).unwrap().build();
+        assert_eq!(system.config.gravity, Vec2::new(0.0, 50.0));
+        assert_eq!(system.config.overflow, OverflowPolicy::RecycleOldest);
+        assert_eq!(system.config.max_particles, 10000);
+        assert_eq!(system.emitters.len(), 1);
+        assert_eq!(system.emitters[0].settings.rate, 100.0);
+        assert!(system.emitters[0].settings.alpha_over_life.is_some());
+        assert!(forces.is_empty());
+    }
+
+    #[test]
+    fn curve_keys_are_sorted_on_load() {
+        let scene = Scene::from_json(FOUNTAIN).unwrap();
+        let curve = scene.emitters[1].size_over_life.as_ref().unwrap();
+        assert_eq!(curve.keys(), &[(0.0, 1.0), (1.0, 0.2)]);
+    }
+
+    #[test]
+    fn exported_scenes_replay_the_same_without_run_time_state() {
+        let scene = Scene::from_json(FOUNTAIN).unwrap();
+        let (mut original, forces) = scene.build();
+        run(&mut original, &forces, 120);
+        assert!(original.particle_count() > 100);
+
+        // Exported after the bursts fired, yet they fire again on reload
+        let exported = original.to_scene(&forces).to_json();
+        assert!(!exported.contains("fired"));
+        let (mut reloaded, forces) = Scene::from_json(&exported).unwrap().build();
+        run(&mut reloaded, &forces, 120);
+        let (mut fresh, forces) = scene.build();
+        run(&mut fresh, &forces, 120);
+        assert_same_particles(&fresh, &reloaded);
+    }
+
+    #[test]
+    fn applying_the_current_scene_changes_nothing() {
+        let scene = Scene::from_json(FOUNTAIN).unwrap();
+        let (mut applied, forces) = scene.build();
+        let (mut untouched, _) = scene.build();
+        run(&mut applied, &forces, 60);
+        run(&mut untouched, &forces, 60);
+
+        // Burst progress and timers survive, so nothing fires twice
+        let exported = applied.to_scene(&forces);
+        let forces = applied.apply_scene(&exported);
+        run(&mut applied, &forces, 120);
+        run(&mut untouched, &forces, 120);
+        assert_same_particles(&untouched, &applied);
+    }
+
+    #[test]
+    fn applying_a_seeded_scene_to_a_fresh_system_matches_build() {
+        let scene = Scene::from_json(FOUNTAIN).unwrap();
+        let (mut built, forces) = scene.build();
+        let mut applied = ParticleSystem::new();
+        assert_eq!(applied.apply_scene(&scene).len(), forces.len());
+
+        run(&mut built, &forces, 120);
+        run(&mut applied, &forces, 120);
+        assert_same_particles(&built, &applied);
+    }
+
+    #[test]
+    fn errors_point_at_the_offending_spot() {
+        let reject = |source: &str| Scene::from_json(source).unwrap_err();
+
+        let typo = FOUNTAIN.replace("\"rate\": 150.0", "\"rat\": 150.0");
+        assert!(matches!(
+            reject(&typo),
+            SceneError::Syntax {
+                line: 13,
+                column: 11,
+                ..
+            }
+        ));
+        let broken = FOUNTAIN.replace("[400.0, 600.0]", "[400.0 600.0]");
+        assert!(matches!(
+            reject(&broken),
+            SceneError::Syntax {
+                line: 12,
+                column: 26,
+                ..
+            }
+        ));
+        assert_eq!(
+            reject(&FOUNTAIN.replace("\"version\": 1", "\"version\": 2")),
+            SceneError::UnsupportedVersion(2)
+        );
+        assert_eq!(
+            reject(&FOUNTAIN.replace("\"emitter\": 0", "\"emitter\": 7")),
+            SceneError::MissingEmitter(7)
+        );
+    }
+}
@@ -0,0 +1,390 @@
+//! Emission shapes for spawning particles over areas and edges
+
+use crate::collision::Outline;
+use crate::physics::Vec2;
+use rand::Rng;
+
+/// Area or edge particles are spawned from
+///
+/// Local shapes are placed relative to the emitter position. `Outline`
+/// uses the outline's own coordinates, so it can follow a detected person.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EmitterShape {
+    /// The emitter position itself
+    #[default]
+    Point,
+
+    /// Filled disc
+    Circle { radius: f32 },
+
+    /// Band between two radii; equal radii give the circle's edge
+    Ring {
+        inner_radius: f32,
+        outer_radius: f32,
+    },
+
+    /// Line segment between two points
+    Line { start: Vec2, end: Vec2 },
+
+    /// Filled axis-aligned rectangle centered on the emitter
+    Rectangle { half_extents: Vec2 },
+
+    /// Closed polygon, filled or along its edges only
+    Polygon { points: Vec<Vec2>, edge_only: bool },
+
+    /// Edges of a detected outline, in world coordinates
+    Outline(Outline),
+}
+
+/// How sample points are picked on a shape
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ShapeSampling {
+    /// Independent uniform random points
+    #[default]
+    Random,
+
+    /// Low-discrepancy sequence that spreads consecutive spawns evenly
+    Even,
+}
+
+/// Point on a shape with the surface normal there, if it has one
+#[derive(Clone, Copy, Debug)]
+pub struct ShapeSample {
+    pub position: Vec2,
+    /// Outward unit normal; `None` for points and degenerate samples
+    pub normal: Option<Vec2>,
+}
+
+/// Most attempts made to find a point inside a polygon by rejection
+const MAX_REJECTION_ATTEMPTS: u32 = 32;
+
+impl EmitterShape {
+    /// Samples a spawn point on the shape placed at `origin`
+    ///
+    /// `sequence` is the emitter's running spawn counter and drives
+    /// `ShapeSampling::Even`; random sampling draws from `rng` instead.
+    pub fn sample(
+        &self,
+        origin: Vec2,
+        sampling: ShapeSampling,
+        sequence: &mut u32,
+        rng: &mut impl Rng,
+    ) -> ShapeSample {
+        let mut sampler = Sampler {
+            sampling,
+            sequence,
+            rng,
+        };
+
+        match self {
+            EmitterShape::Point => ShapeSample {
+                position: origin,
+                normal: None,
+            },
+
+            EmitterShape::Circle { radius } => {
+                // sqrt keeps the density uniform over the disc's area
+                let (u, v) = sampler.next_2d();
+                radial_sample(origin, radius * u.sqrt(), v)
+            }
+
+            EmitterShape::Ring {
+                inner_radius,
+                outer_radius,
+            } => {
+                let (u, v) = sampler.next_2d();
+                let (inner_sq, outer_sq) =
+                    (inner_radius * inner_radius, outer_radius * outer_radius);
+                radial_sample(origin, (inner_sq + (outer_sq - inner_sq) * u).sqrt(), v)
+            }
+
+            EmitterShape::Line { start, end } => {
+                let t = sampler.next_1d();
+                let direction = *end - *start;
+                ShapeSample {
+                    position: origin + *start + direction * t,
+                    normal: non_zero(direction.perp().normalize_or_zero()),
+                }
+            }
+
+            EmitterShape::Rectangle { half_extents } => {
+                let (u, v) = sampler.next_2d();
+                let offset = Vec2::new(u * 2.0 - 1.0, v * 2.0 - 1.0) * *half_extents;
+                ShapeSample {
+                    position: origin + offset,
+                    normal: non_zero(offset.normalize_or_zero()),
+                }
+            }
+
+            EmitterShape::Polygon { points, edge_only } => {
+                let edges = polygon_edges(points, origin);
+                if *edge_only {
+                    edge_sample(edges, sampler.next_1d())
+                } else {
+                    interior_sample(points, origin, &mut sampler)
+                }
+            }
+
+            EmitterShape::Outline(outline) => edge_sample(
+                outline
+                    .segments
+                    .iter()
+                    .map(|segment| (segment.start, segment.end)),
+                sampler.next_1d(),
+            ),
+        }
+    }
+}
+
+/// Point at `radius` from `origin` at the angle given by `turn` in `[0, 1)`
+fn radial_sample(origin: Vec2, radius: f32, turn: f32) -> ShapeSample {
+    let (sin, cos) = libm::sincosf(turn * std::f32::consts::TAU);
+    let direction = Vec2::new(cos, sin);
+    ShapeSample {
+        position: origin + direction * radius,
+        normal: Some(direction),
+    }
+}
+
+fn non_zero(v: Vec2) -> Option<Vec2> {
+    (v != Vec2::ZERO).then_some(v)
+}
+
+/// Closed edge loop through `points`, offset by `origin`
+fn polygon_edges(points: &[Vec2], origin: Vec2) -> impl Iterator<Item = (Vec2, Vec2)> + Clone + '_ {
+    (0..points.len()).map(move |i| (origin + points[i], origin + points[(i + 1) % points.len()]))
+}
+
+/// Samples a point at fraction `t` of the total length of a closed edge loop
+fn edge_sample(edges: impl Iterator<Item = (Vec2, Vec2)> + Clone, t: f32) -> ShapeSample {
+    let perimeter: f32 = edges.clone().map(|(start, end)| start.distance(end)).sum();
+
+    // Twice the signed area tells the winding, and with it which side is out
+    let winding: f32 = edges
+        .clone()
+        .map(|(start, end)| start.perp_dot(end))
+        .sum::<f32>()
+        .signum();
+
+    let mut remaining = t * perimeter;
+    let mut last = None;
+    for (start, end) in edges {
+        let length = start.distance(end);
+        last = Some((start, end, 1.0));
+        if remaining <= length && length > 0.0 {
+            last = Some((start, end, remaining / length));
+            break;
+        }
+        remaining -= length;
+    }
+
+    let Some((start, end, fraction)) = last else {
+        return ShapeSample {
+            position: Vec2::ZERO,
+            normal: None,
+        };
+    };
+
+    let direction = end - start;
+    ShapeSample {
+        position: start + direction * fraction,
+        // perp() is the left normal, which points inward for positive winding
+        normal: non_zero(-direction.perp().normalize_or_zero() * winding),
+    }
+}
+
+/// Samples a point inside a polygon by rejection from its bounding box
+fn interior_sample<R: Rng>(points: &[Vec2], origin: Vec2, sampler: &mut Sampler<R>) -> ShapeSample {
+    let Some(&first) = points.first() else {
+        return ShapeSample {
+            position: origin,
+            normal: None,
+        };
+    };
+
+    let (min, max) = points.iter().fold((first, first), |(min, max), &point| {
+        (min.min(point), max.max(point))
+    });
+    let centroid = points.iter().sum::<Vec2>() / points.len() as f32;
+
+    for _ in 0..MAX_REJECTION_ATTEMPTS {
+        let (u, v) = sampler.next_2d();
+        let local = min + (max - min) * Vec2::new(u, v);
+        if polygon_contains(points, local) {
+            return ShapeSample {
+                position: origin + local,
+                normal: non_zero((local - centroid).normalize_or_zero()),
+            };
+        }
+    }
+
+    ShapeSample {
+        position: origin + centroid,
+        normal: None,
+    }
+}
+
+/// Even-odd point in polygon test
+fn polygon_contains(points: &[Vec2], point: Vec2) -> bool {
+    let mut inside = false;
+    for (i, &start) in points.iter().enumerate() {
+        let end = points[(i + 1) % points.len()];
+        if (start.y > point.y) != (end.y > point.y) {
+            let x = start.x + (point.y - start.y) / (end.y - start.y) * (end.x - start.x);
+            if point.x < x {
+                inside = !inside;
+            }
+        }
+    }
+    inside
+}
+
+/// Source of unit-interval samples for one `EmitterShape::sample` call
+struct Sampler<'a, R> {
+    sampling: ShapeSampling,
+    sequence: &'a mut u32,
+    rng: &'a mut R,
+}
+
+impl<R: Rng> Sampler<'_, R> {
+    fn advance(&mut self) -> f64 {
+        *self.sequence = self.sequence.wrapping_add(1);
+        *self.sequence as f64
+    }
+
+    /// Value in `[0, 1)`; even sampling uses the golden-ratio sequence
+    fn next_1d(&mut self) -> f32 {
+        const ALPHA: f64 = 0.618_033_988_749_894_9;
+        match self.sampling {
+            ShapeSampling::Random => self.rng.gen(),
+            ShapeSampling::Even => (0.5 + ALPHA * self.advance()).fract() as f32,
+        }
+    }
+
+    /// Point in `[0, 1)^2`; even sampling uses the plastic-number R2 sequence
+    fn next_2d(&mut self) -> (f32, f32) {
+        const ALPHA: (f64, f64) = (0.754_877_666_246_692_7, 0.569_840_290_998_053_3);
+        match self.sampling {
+            ShapeSampling::Random => (self.rng.gen(), self.rng.gen()),
+            ShapeSampling::Even => {
+                let n = self.advance();
+                (
+                    (0.5 + ALPHA.0 * n).fract() as f32,
+                    (0.5 + ALPHA.1 * n).fract() as f32,
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::particles::ParticleRng;
+    use rand::SeedableRng;
+
+    /// Samples `count` points from `shape` placed at `origin`
+    fn samples(
+        shape: &EmitterShape,
+        origin: Vec2,
+        sampling: ShapeSampling,
+        count: usize,
+    ) -> Vec<ShapeSample> {
+        let mut rng = ParticleRng::seed_from_u64(4);
+        let mut sequence = 0;
+        (0..count)
+            .map(|_| shape.sample(origin, sampling, &mut sequence, &mut rng))
+            .collect()
+    }
+
+    fn square(clockwise: bool) -> Vec<Vec2> {
+        let mut points = vec![
+            Vec2::new(-1.0, -1.0),
+            Vec2::new(1.0, -1.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(-1.0, 1.0),
+        ];
+        if clockwise {
+            points.reverse();
+        }
+        points
+    }
+
+    #[test]
+    fn filled_polygons_spawn_inside() {
+        // L shape whose bounding box corner is outside the polygon
+        let points = vec![
+            Vec2::new(0.0, 0.0),
+            Vec2::new(4.0, 0.0),
+            Vec2::new(4.0, 2.0),
+            Vec2::new(2.0, 2.0),
+            Vec2::new(2.0, 4.0),
+            Vec2::new(0.0, 4.0),
+        ];
+        let origin = Vec2::new(10.0, 20.0);
+        let shape = EmitterShape::Polygon {
+            points: points.clone(),
+            edge_only: false,
+        };
+
+        for sampling in [ShapeSampling::Random, ShapeSampling::Even] {
+            for sample in samples(&shape, origin, sampling, 500) {
+                assert!(
+                    polygon_contains(&points, sample.position - origin),
+                    "{:?} outside with {:?}",
+                    sample.position,
+                    sampling
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn even_edge_samples_cover_the_edges_evenly() {
+        let shape = EmitterShape::Polygon {
+            points: square(false),
+            edge_only: true,
+        };
+
+        // Count samples on the bottom, right, top and left edge
+        let mut per_edge = [0; 4];
+        for sample in samples(&shape, Vec2::ZERO, ShapeSampling::Even, 400) {
+            let Vec2 { x, y } = sample.position;
+            assert!((x.abs().max(y.abs()) - 1.0).abs() < 1e-5, "{:?}", sample);
+            let edge = if (y + 1.0).abs() < 1e-5 {
+                0
+            } else if (x - 1.0).abs() < 1e-5 {
+                1
+            } else if (y - 1.0).abs() < 1e-5 {
+                2
+            } else {
+                3
+            };
+            per_edge[edge] += 1;
+        }
+        assert!(
+            per_edge.iter().all(|&n| (98..=102).contains(&n)),
+            "{:?}",
+            per_edge
+        );
+    }
+
+    #[test]
+    fn edge_normals_point_outward_for_either_winding() {
+        for clockwise in [false, true] {
+            let shape = EmitterShape::Polygon {
+                points: square(clockwise),
+                edge_only: true,
+            };
+            let origin = Vec2::new(5.0, 5.0);
+            for sample in samples(&shape, origin, ShapeSampling::Random, 100) {
+                let normal = sample.normal.expect("edges have normals");
+                let outward = (sample.position - origin).dot(normal);
+                assert!(outward > 0.0, "{:?} with clockwise {}", sample, clockwise);
+                assert!((normal.length() - 1.0).abs() < 1e-5);
+            }
+        }
+    }
+}
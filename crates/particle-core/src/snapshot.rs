@@ -0,0 +1,208 @@
+//! Serializable snapshots of a running particle system
+//!
+//! A snapshot holds everything needed to continue a simulation exactly
+//! where it left off: particles, emitters with their timers and random
+//! state, colliders, configuration and the forces passed to `update`.
+//! Restoring one and stepping with the same frame times reproduces the
+//! original run bit for bit.
+
+use crate::collision::Outline;
+use crate::particles::{Emitter, Particle, ParticleConfig, ParticleRng};
+use crate::physics::Force;
+use crate::storage::ParticleStorage;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Snapshot format written by this version of the crate
+pub const SNAPSHOT_VERSION: u32 = 2;
+
+/// Saved state of a `ParticleSystem`, see `ParticleSystem::snapshot`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// Format version, checked by `ParticleSystem::restore`
+    pub version: u32,
+    pub config: ParticleConfig,
+    pub particles: ParticleStorage,
+    pub emitters: Vec<Emitter>,
+    /// Cycles fired of each emitter's bursts, which bursts do not serialize
+    pub(crate) burst_cycles: Vec<Vec<u32>>,
+    pub colliders: Vec<Outline>,
+    #[serde(default)]
+    pub zones: Vec<Outline>,
+    /// Forces the system was updated with when the snapshot was taken
+    pub forces: Vec<Force>,
+    pub(crate) rng: ParticleRng,
+    pub(crate) time_accumulator: f32,
+    pub(crate) interpolation_alpha: f32,
+    pub(crate) first_emitter: usize,
+    pub(crate) next_emitter_id: u32,
+}
+
+/// Reason a snapshot could not be restored
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SnapshotError {
+    /// Written by an incompatible version of the format
+    UnsupportedVersion(u32),
+    /// Particle streams or handles do not line up
+    InconsistentParticles,
+    /// A particle or sub-emitter refers to an emitter that does not exist
+    MissingEmitter(usize),
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnsupportedVersion(version) => write!(
+                f,
+                "unsupported snapshot version {} (expected {})",
+                version, SNAPSHOT_VERSION
+            ),
+            Self::InconsistentParticles => write!(f, "snapshot particle storage is inconsistent"),
+            Self::MissingEmitter(index) => {
+                write!(f, "snapshot refers to missing emitter {}", index)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+impl Snapshot {
+    /// Checks that the snapshot can be simulated without panicking
+    pub(crate) fn validate(&self) -> Result<(), SnapshotError> {
+        if self.version != SNAPSHOT_VERSION {
+            return Err(SnapshotError::UnsupportedVersion(self.version));
+        }
+        if !self.particles.is_consistent() {
+            return Err(SnapshotError::InconsistentParticles);
+        }
+
+        let particle_emitters = self
+            .particles
+            .emitters()
+            .iter()
+            .filter(|&&emitter| emitter != Particle::NO_EMITTER)
+            .map(|&emitter| emitter as usize);
+        let sub_emitters = self
+            .emitters
+            .iter()
+            .flat_map(|emitter| &emitter.settings.sub_emitters)
+            .map(|sub| sub.emitter);
+        match particle_emitters
+            .chain(sub_emitters)
+            .find(|&emitter| emitter >= self.emitters.len())
+        {
+            Some(emitter) => Err(SnapshotError::MissingEmitter(emitter)),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curves::Gradient;
+    use crate::emission::{Burst, SubEmitter, SubEmitterTrigger};
+    use crate::particles::ParticleSystem;
+    use crate::physics::Vec2;
+
+    const DT: f32 = 1.0 / 60.0;
+
+    /// Scene using emitter timers, bursts, sub-emitters, colliders and curves
+    fn scene() -> (ParticleSystem, Vec<Force>) {
+        let mut system = ParticleSystem::with_config(ParticleConfig {
+            seed: Some(11),
+            max_particles: 2000,
+            ..ParticleConfig::default()
+        });
+
+        let mut sparks = Emitter::new(Vec2::ZERO);
+        sparks.settings.enabled = false;
+        sparks.settings.spread = std::f32::consts::PI;
+        sparks.settings.particle_lifetime = 0.5;
+
+        let mut fountain = Emitter::new(Vec2::new(400.0, 500.0));
+        fountain.settings.rate = 180.0;
+        fountain.settings.direction = -std::f32::consts::FRAC_PI_2;
+        fountain.settings.initial_velocity = 250.0;
+        fountain.settings.particle_lifetime = 2.0;
+        fountain.settings.duration = Some(1.5);
+        fountain.settings.looping = true;
+        fountain.settings.bursts = vec![Burst::new(0.25, 40)];
+        fountain.settings.color_over_life =
+            Some(Gradient::linear([1.0, 0.8, 0.2, 1.0], [0.8, 0.1, 0.1, 0.0]));
+        fountain.settings.sub_emitters = vec![SubEmitter::new(SubEmitterTrigger::Death, 0, 3)];
+
+        system.add_emitter(sparks);
+        system.add_emitter(fountain);
+        system.colliders.push(Outline::from_points(vec![
+            Vec2::new(300.0, 200.0),
+            Vec2::new(500.0, 200.0),
+            Vec2::new(500.0, 240.0),
+            Vec2::new(300.0, 240.0),
+        ]));
+
+        let forces = vec![
+            Force::wind(Vec2::new(1.0, 0.0), 30.0),
+            Force::attractor(Vec2::new(400.0, 300.0), 2.0e5, 300.0),
+        ];
+        (system, forces)
+    }
+
+    /// Snapshot of the scene a second and a half in, after a JSON round trip
+    fn saved_scene() -> (ParticleSystem, Vec<Force>, Snapshot) {
+        let (mut system, forces) = scene();
+        for _ in 0..90 {
+            system.update(DT, &forces);
+        }
+        let json = serde_json::to_string(&system.snapshot(&forces)).unwrap();
+        (system, forces, serde_json::from_str(&json).unwrap())
+    }
+
+    #[test]
+    fn restored_snapshots_continue_bit_for_bit() {
+        let (mut original, forces, snapshot) = saved_scene();
+        let mut restored = ParticleSystem::new();
+        let restored_forces = restored.restore(snapshot).unwrap();
+        assert_eq!(restored_forces.len(), forces.len());
+
+        // Long enough for the looping bursts to fire again
+        for _ in 0..120 {
+            original.update(DT, &forces);
+            restored.update(DT, &restored_forces);
+        }
+
+        assert_eq!(original.particle_count(), restored.particle_count());
+        assert_eq!(original.particles.ids(), restored.particles.ids());
+        for (a, b) in original.particles.iter().zip(restored.particles.iter()) {
+            assert_eq!(bytemuck::bytes_of(&a), bytemuck::bytes_of(&b));
+        }
+        for (a, b) in original.emitters.iter().zip(&restored.emitters) {
+            assert_eq!(a.time(), b.time());
+            assert_eq!(a.id(), b.id());
+        }
+    }
+
+    #[test]
+    fn damaged_snapshots_leave_the_system_untouched() {
+        let (original, _, snapshot) = saved_scene();
+        let mut restored = ParticleSystem::new();
+        restored.restore(snapshot.clone()).unwrap();
+
+        let mut future = snapshot.clone();
+        future.version += 1;
+        assert!(matches!(
+            restored.restore(future),
+            Err(SnapshotError::UnsupportedVersion(_))
+        ));
+
+        let mut orphaned = snapshot;
+        orphaned.emitters.truncate(1);
+        assert!(matches!(
+            restored.restore(orphaned),
+            Err(SnapshotError::MissingEmitter(_))
+        ));
+        assert_eq!(original.particle_count(), restored.particle_count());
+        assert_eq!(restored.emitters.len(), 2);
+    }
+}
@@ -0,0 +1,86 @@
+//! Statistics and phase timings of the simulation step
+
+use std::time::Duration;
+
+/// Wall-clock time spent in the phases of `ParticleSystem::update`
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PhaseTimings {
+    /// Evaluating forces, summed over every integrator stage, fluid
+    /// pressure, viscosity and surface tension, and flocking
+    pub forces: Duration,
+    /// Moving particles, drag and speed curves, excluding `forces`
+    pub integration: Duration,
+    /// Pushing particles out of colliders and world bounds, crossing zones
+    pub collision: Duration,
+    /// Spawning from emitters and sub-emitters
+    pub emission: Duration,
+    /// The whole update, including appearance curves and removing the dead
+    pub total: Duration,
+}
+
+/// What happened during the last `ParticleSystem::update`
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StepStats {
+    /// Particles emitted, including those of sub-emitters
+    pub spawned: usize,
+    /// Particles that ran out of life or were recycled to make room
+    pub died: usize,
+    /// Particles that found no room under the overflow policy
+    pub dropped: u64,
+    /// Live particles after the step
+    pub particles: usize,
+    /// Live particles of each emitter, indexed like `ParticleSystem::emitters`
+    pub emitter_particles: Vec<usize>,
+    /// Mean speed of the live particles
+    pub average_speed: f32,
+    /// Sum of ½mv² over the live particles
+    pub kinetic_energy: f32,
+    pub timings: PhaseTimings,
+}
+
+/// Measures elapsed wall-clock time
+///
+/// `std::time::Instant` is unavailable on `wasm32-unknown-unknown`, so the
+/// browser's `performance.now()` is used there instead. Outside a window,
+/// such as in a worker, no time is measured.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Stopwatch {
+    #[cfg(not(target_arch = "wasm32"))]
+    start: std::time::Instant,
+    /// Start time in milliseconds
+    #[cfg(target_arch = "wasm32")]
+    start: f64,
+}
+
+impl Stopwatch {
+    /// Starts measuring from now
+    pub(crate) fn start() -> Self {
+        Self { start: now() }
+    }
+
+    /// Returns the time since `start`
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    /// Returns the time since `start`
+    #[cfg(target_arch = "wasm32")]
+    pub(crate) fn elapsed(&self) -> Duration {
+        Duration::from_secs_f64((now() - self.start).max(0.0) / 1000.0)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn now() -> std::time::Instant {
+    std::time::Instant::now()
+}
+
+#[cfg(target_arch = "wasm32")]
+fn now() -> f64 {
+    web_sys::window()
+        .and_then(|window| window.performance())
+        .map_or(0.0, |performance| performance.now())
+}
@@ -0,0 +1,515 @@
+//! Structure-of-arrays particle storage
+
+use crate::particles::Particle;
+use crate::physics::Vec2;
+
+/// Stable handle to a particle
+///
+/// Stays valid for as long as the particle lives, no matter how the storage
+/// is rearranged. Once the particle dies its slot is reused with a new
+/// generation, so stale handles resolve to `None` instead of another particle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ParticleId {
+    index: u32,
+    generation: u32,
+}
+
+impl ParticleId {
+    /// Returns the slot index of this handle
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// Returns the generation of this handle
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+}
+
+/// Entry of the slot table mapping handles to dense indices
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Slot {
+    generation: u32,
+    /// Index into the streams, `FREE` while the slot is unused
+    dense: u32,
+}
+
+impl Slot {
+    const FREE: u32 = u32::MAX;
+}
+
+/// Particle attributes stored as one contiguous stream per attribute
+///
+/// Each stream is a plain slice, so update loops touch only the attributes
+/// they need and the renderer can upload streams to the GPU without copying
+/// them into an interleaved buffer first. `Particle` remains the by-value
+/// view of a single entry, see `get`, `set` and `iter`.
+///
+/// Streams stay densely packed: removing a particle moves the last one into
+/// its place. A slot table with a free list maps each `ParticleId` to its
+/// current dense index, so handles survive the move.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ParticleStorage {
+    ids: Vec<ParticleId>,
+    positions: Vec<Vec2>,
+    prev_positions: Vec<Vec2>,
+    velocities: Vec<Vec2>,
+    lives: Vec<f32>,
+    max_lives: Vec<f32>,
+    sizes: Vec<f32>,
+    base_sizes: Vec<f32>,
+    colors: Vec<[f32; 4]>,
+    base_colors: Vec<[f32; 4]>,
+    masses: Vec<f32>,
+    emitters: Vec<u32>,
+    depths: Vec<u32>,
+    groups: Vec<u32>,
+    rotations: Vec<f32>,
+    angular_velocities: Vec<f32>,
+    frames: Vec<u32>,
+    slots: Vec<Slot>,
+    free_slots: Vec<u32>,
+}
+
+impl ParticleStorage {
+    /// Creates empty storage
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates empty storage with room for `capacity` particles
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            ids: Vec::with_capacity(capacity),
+            positions: Vec::with_capacity(capacity),
+            prev_positions: Vec::with_capacity(capacity),
+            velocities: Vec::with_capacity(capacity),
+            lives: Vec::with_capacity(capacity),
+            max_lives: Vec::with_capacity(capacity),
+            sizes: Vec::with_capacity(capacity),
+            base_sizes: Vec::with_capacity(capacity),
+            colors: Vec::with_capacity(capacity),
+            base_colors: Vec::with_capacity(capacity),
+            masses: Vec::with_capacity(capacity),
+            emitters: Vec::with_capacity(capacity),
+            depths: Vec::with_capacity(capacity),
+            groups: Vec::with_capacity(capacity),
+            rotations: Vec::with_capacity(capacity),
+            angular_velocities: Vec::with_capacity(capacity),
+            frames: Vec::with_capacity(capacity),
+            slots: Vec::with_capacity(capacity),
+            free_slots: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Returns the number of stored particles
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Returns true if no particles are stored
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Adds a particle and returns its handle
+    ///
+    /// Reuses a free slot when there is one, so pushing below the reserved
+    /// capacity never allocates.
+    pub fn push(&mut self, particle: Particle) -> ParticleId {
+        let dense = self.len() as u32;
+        let id = match self.free_slots.pop() {
+            Some(index) => {
+                let slot = &mut self.slots[index as usize];
+                slot.dense = dense;
+                ParticleId {
+                    index,
+                    generation: slot.generation,
+                }
+            }
+            None => {
+                self.slots.push(Slot {
+                    generation: 0,
+                    dense,
+                });
+                ParticleId {
+                    index: self.slots.len() as u32 - 1,
+                    generation: 0,
+                }
+            }
+        };
+
+        self.ids.push(id);
+        self.positions.push(particle.pos());
+        self.prev_positions.push(Vec2::from(particle.prev_position));
+        self.velocities.push(particle.vel());
+        self.lives.push(particle.life);
+        self.max_lives.push(particle.max_life);
+        self.sizes.push(particle.size);
+        self.base_sizes.push(particle.size);
+        self.colors.push(particle.color);
+        self.base_colors.push(particle.color);
+        self.masses.push(particle.mass);
+        self.emitters.push(particle.emitter);
+        self.depths.push(particle.depth);
+        self.groups.push(particle.groups);
+        self.rotations.push(particle.rotation);
+        self.angular_velocities.push(particle.angular_velocity);
+        self.frames.push(particle.frame);
+        id
+    }
+
+    /// Returns the dense index of a live particle
+    pub fn index_of(&self, id: ParticleId) -> Option<usize> {
+        let slot = self.slots.get(id.index as usize)?;
+        (slot.generation == id.generation && slot.dense != Slot::FREE)
+            .then_some(slot.dense as usize)
+    }
+
+    /// Returns true if the handle refers to a live particle
+    pub fn contains(&self, id: ParticleId) -> bool {
+        self.index_of(id).is_some()
+    }
+
+    /// Returns the handle of the particle at `index`
+    pub fn id(&self, index: usize) -> Option<ParticleId> {
+        self.ids.get(index).copied()
+    }
+
+    /// Returns a copy of the particle with the given handle
+    pub fn get_by_id(&self, id: ParticleId) -> Option<Particle> {
+        self.get(self.index_of(id)?)
+    }
+
+    /// Removes the particle with the given handle and returns it
+    pub fn remove(&mut self, id: ParticleId) -> Option<Particle> {
+        let index = self.index_of(id)?;
+        let particle = self.get(index);
+        self.swap_remove(index);
+        particle
+    }
+
+    /// Returns a copy of the particle at `index`
+    pub fn get(&self, index: usize) -> Option<Particle> {
+        if index >= self.len() {
+            return None;
+        }
+
+        Some(Particle {
+            position: self.positions[index].into(),
+            velocity: self.velocities[index].into(),
+            life: self.lives[index],
+            max_life: self.max_lives[index],
+            size: self.sizes[index],
+            color: self.colors[index],
+            mass: self.masses[index],
+            prev_position: self.prev_positions[index].into(),
+            emitter: self.emitters[index],
+            depth: self.depths[index],
+            groups: self.groups[index],
+            rotation: self.rotations[index],
+            angular_velocity: self.angular_velocities[index],
+            frame: self.frames[index],
+        })
+    }
+
+    /// Overwrites the particle at `index`
+    ///
+    /// Size and color are written as the current, curve-scaled values, like
+    /// `sizes_mut` and `colors_mut`. The base values over-lifetime curves
+    /// scale stay those the particle was pushed with, so `set(index, get(index))`
+    /// changes nothing.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn set(&mut self, index: usize, particle: Particle) {
+        self.positions[index] = particle.pos();
+        self.prev_positions[index] = Vec2::from(particle.prev_position);
+        self.velocities[index] = particle.vel();
+        self.lives[index] = particle.life;
+        self.max_lives[index] = particle.max_life;
+        self.sizes[index] = particle.size;
+        self.colors[index] = particle.color;
+        self.masses[index] = particle.mass;
+        self.emitters[index] = particle.emitter;
+        self.depths[index] = particle.depth;
+        self.groups[index] = particle.groups;
+        self.rotations[index] = particle.rotation;
+        self.angular_velocities[index] = particle.angular_velocity;
+        self.frames[index] = particle.frame;
+    }
+
+    /// Iterates over copies of all particles
+    pub fn iter(&self) -> impl Iterator<Item = Particle> + '_ {
+        (0..self.len()).filter_map(move |index| self.get(index))
+    }
+
+    /// Removes all particles, invalidating every handle
+    pub fn clear(&mut self) {
+        while !self.is_empty() {
+            self.swap_remove(self.len() - 1);
+        }
+    }
+
+    /// Removes particles whose life has run out
+    ///
+    /// Survivors keep their handles but not necessarily their dense index.
+    /// Returns the number of particles removed.
+    pub fn retain_alive(&mut self) -> usize {
+        let mut removed = 0;
+        let mut index = 0;
+        while index < self.len() {
+            if self.lives[index] > 0.0 {
+                index += 1;
+            } else {
+                // The last particle moves into `index` and is checked next
+                self.swap_remove(index);
+                removed += 1;
+            }
+        }
+        removed
+    }
+
+    /// Removes the particle at `index`, moving the last one into its place
+    fn swap_remove(&mut self, index: usize) {
+        let id = self.ids.swap_remove(index);
+        self.positions.swap_remove(index);
+        self.prev_positions.swap_remove(index);
+        self.velocities.swap_remove(index);
+        self.lives.swap_remove(index);
+        self.max_lives.swap_remove(index);
+        self.sizes.swap_remove(index);
+        self.base_sizes.swap_remove(index);
+        self.colors.swap_remove(index);
+        self.base_colors.swap_remove(index);
+        self.masses.swap_remove(index);
+        self.emitters.swap_remove(index);
+        self.depths.swap_remove(index);
+        self.groups.swap_remove(index);
+        self.rotations.swap_remove(index);
+        self.angular_velocities.swap_remove(index);
+        self.frames.swap_remove(index);
+
+        // Point the moved particle's slot at its new dense index
+        if let Some(moved) = self.ids.get(index) {
+            self.slots[moved.index as usize].dense = index as u32;
+        }
+
+        let slot = &mut self.slots[id.index as usize];
+        slot.dense = Slot::FREE;
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free_slots.push(id.index);
+    }
+
+    /// Handles of the particles, in dense order
+    pub fn ids(&self) -> &[ParticleId] {
+        &self.ids
+    }
+
+    /// Positions in 2D space
+    pub fn positions(&self) -> &[Vec2] {
+        &self.positions
+    }
+
+    /// Positions before the last simulation step
+    pub fn prev_positions(&self) -> &[Vec2] {
+        &self.prev_positions
+    }
+
+    /// Velocity vectors
+    pub fn velocities(&self) -> &[Vec2] {
+        &self.velocities
+    }
+
+    /// Remaining lifetimes in seconds
+    pub fn lives(&self) -> &[f32] {
+        &self.lives
+    }
+
+    /// Total lifetimes in seconds
+    pub fn max_lives(&self) -> &[f32] {
+        &self.max_lives
+    }
+
+    /// Visual sizes (radius in pixels)
+    pub fn sizes(&self) -> &[f32] {
+        &self.sizes
+    }
+
+    /// RGBA colors
+    pub fn colors(&self) -> &[[f32; 4]] {
+        &self.colors
+    }
+
+    /// RGBA colors the particles were spawned with, before over-lifetime curves
+    pub fn base_colors(&self) -> &[[f32; 4]] {
+        &self.base_colors
+    }
+
+    /// Masses for physics calculations
+    pub fn masses(&self) -> &[f32] {
+        &self.masses
+    }
+
+    /// Indices of the emitters that spawned each particle
+    pub fn emitters(&self) -> &[u32] {
+        &self.emitters
+    }
+
+    /// Sub-emitter generations above each particle
+    pub fn depths(&self) -> &[u32] {
+        &self.depths
+    }
+
+    /// Group bits, see `GroupMask`
+    pub fn groups(&self) -> &[u32] {
+        &self.groups
+    }
+
+    /// Orientations in radians
+    pub fn rotations(&self) -> &[f32] {
+        &self.rotations
+    }
+
+    /// Spins in radians per second
+    pub fn angular_velocities(&self) -> &[f32] {
+        &self.angular_velocities
+    }
+
+    /// Sprite animation frame indices
+    pub fn frames(&self) -> &[u32] {
+        &self.frames
+    }
+
+    /// Mutable positions
+    pub fn positions_mut(&mut self) -> &mut [Vec2] {
+        &mut self.positions
+    }
+
+    /// Mutable velocities
+    pub fn velocities_mut(&mut self) -> &mut [Vec2] {
+        &mut self.velocities
+    }
+
+    /// Mutable sizes
+    pub fn sizes_mut(&mut self) -> &mut [f32] {
+        &mut self.sizes
+    }
+
+    /// Mutable colors
+    pub fn colors_mut(&mut self) -> &mut [[f32; 4]] {
+        &mut self.colors
+    }
+
+    /// Mutable emitter indices, e.g. to follow emitters that moved
+    pub(crate) fn emitters_mut(&mut self) -> &mut [u32] {
+        &mut self.emitters
+    }
+
+    /// Borrows every stream at once for the simulation update
+    pub(crate) fn streams_mut(&mut self) -> StreamsMut<'_> {
+        StreamsMut {
+            positions: &mut self.positions,
+            prev_positions: &mut self.prev_positions,
+            velocities: &mut self.velocities,
+            lives: &mut self.lives,
+            max_lives: &self.max_lives,
+            sizes: &mut self.sizes,
+            base_sizes: &self.base_sizes,
+            colors: &mut self.colors,
+            base_colors: &self.base_colors,
+            masses: &self.masses,
+            emitters: &self.emitters,
+            groups: &self.groups,
+            rotations: &mut self.rotations,
+            angular_velocities: &self.angular_velocities,
+            frames: &mut self.frames,
+        }
+    }
+
+    /// Returns true if the streams line up and the slot table matches them
+    ///
+    /// Always holds for storage built through this API; used to reject
+    /// damaged or hand-edited snapshots before they are simulated.
+    #[cfg(feature = "serde")]
+    pub(crate) fn is_consistent(&self) -> bool {
+        let len = self.len();
+        let streams = [
+            self.ids.len(),
+            self.prev_positions.len(),
+            self.velocities.len(),
+            self.lives.len(),
+            self.max_lives.len(),
+            self.sizes.len(),
+            self.base_sizes.len(),
+            self.colors.len(),
+            self.base_colors.len(),
+            self.masses.len(),
+            self.emitters.len(),
+            self.depths.len(),
+            self.groups.len(),
+            self.rotations.len(),
+            self.angular_velocities.len(),
+            self.frames.len(),
+        ];
+        if streams.iter().any(|&stream| stream != len) {
+            return false;
+        }
+
+        let live = self.ids.iter().enumerate().all(|(dense, &id)| {
+            self.slots.get(id.index as usize).is_some_and(|slot| {
+                slot.generation == id.generation && slot.dense as usize == dense
+            })
+        });
+        let free = self.free_slots.iter().all(|&index| {
+            self.slots
+                .get(index as usize)
+                .is_some_and(|slot| slot.dense == Slot::FREE)
+        });
+        live && free && self.slots.len() == len + self.free_slots.len()
+    }
+}
+
+/// Borrowed streams used while updating particles
+pub(crate) struct StreamsMut<'a> {
+    pub positions: &'a mut [Vec2],
+    pub prev_positions: &'a mut [Vec2],
+    pub velocities: &'a mut [Vec2],
+    pub lives: &'a mut [f32],
+    pub max_lives: &'a [f32],
+    pub sizes: &'a mut [f32],
+    pub base_sizes: &'a [f32],
+    pub colors: &'a mut [[f32; 4]],
+    pub base_colors: &'a [[f32; 4]],
+    pub masses: &'a [f32],
+    pub emitters: &'a [u32],
+    pub groups: &'a [u32],
+    pub rotations: &'a mut [f32],
+    pub angular_velocities: &'a [f32],
+    pub frames: &'a mut [u32],
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_round_trips_curve_scaled_particles() {
+        let mut storage = ParticleStorage::new();
+        storage.push(Particle::new(Vec2::ZERO, Vec2::ZERO, 1.0, 3.0, [1.0; 4]));
+
+        // As an over-lifetime curve would leave it
+        storage.sizes_mut()[0] = 1.5;
+        storage.colors_mut()[0] = [0.5; 4];
+
+        storage.set(0, storage.get(0).unwrap());
+        let particle = storage.get(0).unwrap();
+        assert_eq!((particle.size, particle.color), (1.5, [0.5; 4]));
+        assert_eq!(storage.base_sizes[0], 3.0);
+        assert_eq!(storage.base_colors[0], [1.0; 4]);
+    }
+}
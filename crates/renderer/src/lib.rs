@@ -7,7 +7,7 @@
 
 pub mod particle_renderer;
 
-pub use particle_renderer::ParticleRenderer;
+pub use particle_renderer::{ParticleRenderer, SpriteAtlas};
 
 /// Main renderer state
 pub struct Renderer {
@@ -1,28 +1,179 @@
 //! Particle rendering using instanced rendering
 
-use particle_core::Particle;
+use particle_core::{ParticleStorage, Vec2};
 use wgpu::util::DeviceExt;
 
+/// Simulation area drawn until `ParticleRenderer::set_viewport` is called
+const DEFAULT_VIEWPORT: [f32; 2] = [1280.0, 720.0];
+
+/// Per-frame values shared by all particle instances
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct Globals {
+    /// Blend factor between previous and current particle positions
+    interpolation_alpha: f32,
+    /// 1 if a sprite atlas is bound, 0 to draw soft circles
+    use_atlas: u32,
+    /// Columns and rows of frames in the sprite atlas
+    atlas_grid: [u32; 2],
+    /// Width and height of the simulation area drawn across the target
+    viewport: [f32; 2],
+    /// Pads the uniform to a multiple of 16 bytes for WebGL
+    _padding: [f32; 2],
+}
+
+/// One GPU buffer per uploaded particle stream
+struct InstanceBuffers {
+    positions: wgpu::Buffer,
+    prev_positions: wgpu::Buffer,
+    sizes: wgpu::Buffer,
+    colors: wgpu::Buffer,
+    rotations: wgpu::Buffer,
+    frames: wgpu::Buffer,
+}
+
+impl InstanceBuffers {
+    fn new(device: &wgpu::Device, max_particles: usize) -> Self {
+        let create = |label: &str, element_size: usize| {
+            device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some(label),
+                size: (element_size * max_particles) as u64,
+                usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            })
+        };
+
+        Self {
+            positions: create("Particle Position Buffer", std::mem::size_of::<Vec2>()),
+            prev_positions: create(
+                "Particle Previous Position Buffer",
+                std::mem::size_of::<Vec2>(),
+            ),
+            sizes: create("Particle Size Buffer", std::mem::size_of::<f32>()),
+            colors: create("Particle Color Buffer", std::mem::size_of::<[f32; 4]>()),
+            rotations: create("Particle Rotation Buffer", std::mem::size_of::<f32>()),
+            frames: create("Particle Frame Buffer", std::mem::size_of::<u32>()),
+        }
+    }
+}
+
+/// Describes a per-instance vertex buffer holding a single attribute
+fn instance_layout(attributes: &[wgpu::VertexAttribute]) -> wgpu::VertexBufferLayout<'_> {
+    wgpu::VertexBufferLayout {
+        array_stride: attributes[0].format.size(),
+        step_mode: wgpu::VertexStepMode::Instance,
+        attributes,
+    }
+}
+
+/// Sprite sheet of equally sized animation frames
+#[derive(Clone, Copy, Debug)]
+pub struct SpriteAtlas<'a> {
+    /// RGBA8 pixels, row by row
+    pub pixels: &'a [u8],
+    pub width: u32,
+    pub height: u32,
+    /// Frames per row
+    pub columns: u32,
+    /// Rows of frames; frames are numbered row by row
+    pub rows: u32,
+}
+
+/// Creates a texture and uploads RGBA8 pixels into it
+fn create_atlas_texture(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    pixels: &[u8],
+    width: u32,
+    height: u32,
+) -> wgpu::Texture {
+    device.create_texture_with_data(
+        queue,
+        &wgpu::TextureDescriptor {
+            label: Some("Particle Sprite Atlas"),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        },
+        wgpu::util::TextureDataOrder::LayerMajor,
+        pixels,
+    )
+}
+
 /// Renders particles using GPU instancing
 pub struct ParticleRenderer {
     render_pipeline: wgpu::RenderPipeline,
     vertex_buffer: wgpu::Buffer,
-    instance_buffer: wgpu::Buffer,
+    instance_buffers: InstanceBuffers,
+    globals_buffer: wgpu::Buffer,
+    globals_bind_group_layout: wgpu::BindGroupLayout,
+    globals_bind_group: wgpu::BindGroup,
+    sampler: wgpu::Sampler,
+    /// Columns and rows of the bound sprite atlas, if any
+    atlas_grid: Option<[u32; 2]>,
+    /// Simulation area drawn across the target, from the origin
+    viewport: [f32; 2],
     max_particles: usize,
 }
 
 impl ParticleRenderer {
     /// Creates a new particle renderer
-    pub fn new(device: &wgpu::Device, format: wgpu::TextureFormat, max_particles: usize) -> Self {
+    pub fn new(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        format: wgpu::TextureFormat,
+        max_particles: usize,
+    ) -> Self {
         let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
             label: Some("Particle Shader"),
             source: wgpu::ShaderSource::Wgsl(include_str!("shaders/particle.wgsl").into()),
         });
 
+        let globals_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("Particle Globals Bind Group Layout"),
+                entries: &[
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 1,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Texture {
+                            sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                            view_dimension: wgpu::TextureViewDimension::D2,
+                            multisampled: false,
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 2,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                        count: None,
+                    },
+                ],
+            });
+
         let render_pipeline_layout =
             device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
                 label: Some("Particle Render Pipeline Layout"),
-                bind_group_layouts: &[],
+                bind_group_layouts: &[&globals_bind_group_layout],
                 push_constant_ranges: &[],
             });
 
@@ -39,19 +190,13 @@ impl ParticleRenderer {
                         step_mode: wgpu::VertexStepMode::Vertex,
                         attributes: &wgpu::vertex_attr_array![0 => Float32x2],
                     },
-                    // Instance buffer (per-particle data)
-                    wgpu::VertexBufferLayout {
-                        array_stride: std::mem::size_of::<Particle>() as wgpu::BufferAddress,
-                        step_mode: wgpu::VertexStepMode::Instance,
-                        attributes: &wgpu::vertex_attr_array![
-                            1 => Float32x2,  // position
-                            2 => Float32x2,  // velocity
-                            3 => Float32,    // life
-                            4 => Float32,    // size
-                            5 => Float32x4,  // color
-                            6 => Float32,    // mass
-                        ],
-                    },
+                    // Instance buffers, one per particle stream
+                    instance_layout(&wgpu::vertex_attr_array![1 => Float32x2]), // position
+                    instance_layout(&wgpu::vertex_attr_array![2 => Float32x2]), // prev_position
+                    instance_layout(&wgpu::vertex_attr_array![3 => Float32]),   // size
+                    instance_layout(&wgpu::vertex_attr_array![4 => Float32x4]), // color
+                    instance_layout(&wgpu::vertex_attr_array![5 => Float32]),   // rotation
+                    instance_layout(&wgpu::vertex_attr_array![6 => Uint32]),    // frame
                 ],
                 compilation_options: Default::default(),
             },
@@ -107,36 +252,191 @@ impl ParticleRenderer {
             usage: wgpu::BufferUsages::VERTEX,
         });
 
-        // Create instance buffer (will be updated each frame)
-        let instance_buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("Particle Instance Buffer"),
-            size: (std::mem::size_of::<Particle>() * max_particles) as u64,
-            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
-            mapped_at_creation: false,
+        // Create instance buffers (will be updated each frame)
+        let instance_buffers = InstanceBuffers::new(device, max_particles);
+
+        let globals_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Particle Globals Buffer"),
+            contents: bytemuck::bytes_of(&Globals {
+                interpolation_alpha: 1.0,
+                use_atlas: 0,
+                atlas_grid: [1, 1],
+                viewport: DEFAULT_VIEWPORT,
+                _padding: [0.0; 2],
+            }),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Particle Sprite Sampler"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
         });
 
+        // Placeholder bound until a sprite atlas is set, never sampled
+        let placeholder = create_atlas_texture(device, queue, &[255; 4], 1, 1);
+
+        let globals_bind_group = Self::create_bind_group(
+            device,
+            &globals_bind_group_layout,
+            &globals_buffer,
+            &placeholder,
+            &sampler,
+        );
+
         Self {
             render_pipeline,
             vertex_buffer,
-            instance_buffer,
+            instance_buffers,
+            globals_buffer,
+            globals_bind_group_layout,
+            globals_bind_group,
+            sampler,
+            atlas_grid: None,
+            viewport: DEFAULT_VIEWPORT,
             max_particles,
         }
     }
 
+    fn create_bind_group(
+        device: &wgpu::Device,
+        layout: &wgpu::BindGroupLayout,
+        globals_buffer: &wgpu::Buffer,
+        atlas: &wgpu::Texture,
+        sampler: &wgpu::Sampler,
+    ) -> wgpu::BindGroup {
+        let view = atlas.create_view(&wgpu::TextureViewDescriptor::default());
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Particle Globals Bind Group"),
+            layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: globals_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(&view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::Sampler(sampler),
+                },
+            ],
+        })
+    }
+
+    /// Draws particles as sprites from an atlas instead of soft circles
+    ///
+    /// Each particle draws the frame given by its `frame` index, tinted by
+    /// its color and turned by its rotation.
+    pub fn set_sprite_atlas(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        atlas: &SpriteAtlas,
+    ) {
+        let SpriteAtlas {
+            pixels,
+            width,
+            height,
+            columns,
+            rows,
+        } = *atlas;
+        let atlas = create_atlas_texture(device, queue, pixels, width, height);
+        self.globals_bind_group = Self::create_bind_group(
+            device,
+            &self.globals_bind_group_layout,
+            &self.globals_buffer,
+            &atlas,
+            &self.sampler,
+        );
+        self.atlas_grid = Some([columns.max(1), rows.max(1)]);
+    }
+
+    /// Sets the simulation area drawn across the whole target
+    ///
+    /// Positions from `(0, 0)` to `(width, height)` fill the target, with
+    /// the origin at the top left. Match it to the world bounds so that
+    /// particles leave the screen where they reach an edge. Zero sizes are
+    /// ignored.
+    pub fn set_viewport(&mut self, width: f32, height: f32) {
+        if width > 0.0 && height > 0.0 {
+            self.viewport = [width, height];
+        }
+    }
+
+    /// Resizes the instance buffers to hold `max_particles` particles
+    ///
+    /// Call when the particle limit changes, e.g. after loading a scene;
+    /// particles past the limit the buffers were created with are not drawn.
+    pub fn set_max_particles(&mut self, device: &wgpu::Device, max_particles: usize) {
+        if max_particles != self.max_particles {
+            self.instance_buffers = InstanceBuffers::new(device, max_particles);
+            self.max_particles = max_particles;
+        }
+    }
+
     /// Renders the particles
+    ///
+    /// `interpolation_alpha` blends each particle between its previous and
+    /// current position, see `ParticleSystem::interpolation_alpha`.
     pub fn render(
         &self,
         encoder: &mut wgpu::CommandEncoder,
         view: &wgpu::TextureView,
         queue: &wgpu::Queue,
-        particles: &[Particle],
+        particles: &ParticleStorage,
+        interpolation_alpha: f32,
     ) {
-        // Update instance buffer with particle data
-        if !particles.is_empty() {
+        queue.write_buffer(
+            &self.globals_buffer,
+            0,
+            bytemuck::bytes_of(&Globals {
+                interpolation_alpha,
+                use_atlas: self.atlas_grid.is_some() as u32,
+                atlas_grid: self.atlas_grid.unwrap_or([1, 1]),
+                viewport: self.viewport,
+                _padding: [0.0; 2],
+            }),
+        );
+
+        // The instance buffers only hold `max_particles` entries
+        let count = particles.len().min(self.max_particles);
+
+        // Upload the streams the shader reads straight from storage
+        if count > 0 {
+            let buffers = &self.instance_buffers;
+            queue.write_buffer(
+                &buffers.positions,
+                0,
+                bytemuck::cast_slice(&particles.positions()[..count]),
+            );
+            queue.write_buffer(
+                &buffers.prev_positions,
+                0,
+                bytemuck::cast_slice(&particles.prev_positions()[..count]),
+            );
+            queue.write_buffer(
+                &buffers.sizes,
+                0,
+                bytemuck::cast_slice(&particles.sizes()[..count]),
+            );
+            queue.write_buffer(
+                &buffers.colors,
+                0,
+                bytemuck::cast_slice(&particles.colors()[..count]),
+            );
+            queue.write_buffer(
+                &buffers.rotations,
+                0,
+                bytemuck::cast_slice(&particles.rotations()[..count]),
+            );
             queue.write_buffer(
-                &self.instance_buffer,
+                &buffers.frames,
                 0,
-                bytemuck::cast_slice(particles),
+                bytemuck::cast_slice(&particles.frames()[..count]),
             );
         }
 
@@ -162,9 +462,15 @@ impl ParticleRenderer {
         });
 
         render_pass.set_pipeline(&self.render_pipeline);
+        render_pass.set_bind_group(0, &self.globals_bind_group, &[]);
         render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
-        render_pass.set_vertex_buffer(1, self.instance_buffer.slice(..));
-        render_pass.draw(0..6, 0..particles.len() as u32);
+        render_pass.set_vertex_buffer(1, self.instance_buffers.positions.slice(..));
+        render_pass.set_vertex_buffer(2, self.instance_buffers.prev_positions.slice(..));
+        render_pass.set_vertex_buffer(3, self.instance_buffers.sizes.slice(..));
+        render_pass.set_vertex_buffer(4, self.instance_buffers.colors.slice(..));
+        render_pass.set_vertex_buffer(5, self.instance_buffers.rotations.slice(..));
+        render_pass.set_vertex_buffer(6, self.instance_buffers.frames.slice(..));
+        render_pass.draw(0..6, 0..count as u32);
     }
 }
 
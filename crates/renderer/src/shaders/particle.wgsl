@@ -1,22 +1,42 @@
 // Vertex shader for particle rendering with instancing
 
+struct Globals {
+    interpolation_alpha: f32,
+    // 1 if a sprite atlas is bound, 0 to draw soft circles
+    use_atlas: u32,
+    // Columns and rows of frames in the atlas
+    atlas_grid: vec2<u32>,
+    // Width and height of the simulation area drawn across the target
+    viewport: vec2<f32>,
+}
+
+@group(0) @binding(0)
+var<uniform> globals: Globals;
+
+@group(0) @binding(1)
+var atlas: texture_2d<f32>;
+
+@group(0) @binding(2)
+var atlas_sampler: sampler;
+
 struct VertexInput {
     @location(0) position: vec2<f32>,
 }
 
 struct InstanceInput {
     @location(1) particle_pos: vec2<f32>,
-    @location(2) velocity: vec2<f32>,
-    @location(3) life: f32,
-    @location(4) size: f32,
-    @location(5) color: vec4<f32>,
-    @location(6) mass: f32,
+    @location(2) prev_pos: vec2<f32>,
+    @location(3) size: f32,
+    @location(4) color: vec4<f32>,
+    @location(5) rotation: f32,
+    @location(6) frame: u32,
 }
 
 struct VertexOutput {
     @builtin(position) clip_position: vec4<f32>,
     @location(0) color: vec4<f32>,
     @location(1) uv: vec2<f32>,
+    @location(2) @interpolate(flat) frame: u32,
 }
 
 @vertex
@@ -26,27 +46,47 @@ fn vs_main(
 ) -> VertexOutput {
     var out: VertexOutput;
     
-    // Scale the quad by particle size
-    let scaled_pos = vertex.position * instance.size;
+    // Turn the quad by the particle's rotation, then scale it by its size
+    let c = cos(instance.rotation);
+    let s = sin(instance.rotation);
+    let rotated = vec2<f32>(
+        vertex.position.x * c - vertex.position.y * s,
+        vertex.position.x * s + vertex.position.y * c,
+    );
+    let scaled_pos = rotated * instance.size;
     
-    // Transform to screen space (assuming normalized coordinates)
-    // For now, simple pass-through. TODO: Add proper projection matrix
-    let world_pos = scaled_pos + instance.particle_pos;
+    // Blend between the last two simulation steps for smooth motion
+    let particle_pos = mix(instance.prev_pos, instance.particle_pos, globals.interpolation_alpha);
+    let world_pos = scaled_pos + particle_pos;
     
-    // Convert to clip space (assuming canvas is 0-1280 x 0-720 or similar)
-    // This is a simplified version - should use proper uniforms
-    let clip_x = (world_pos.x / 640.0) - 1.0;
-    let clip_y = 1.0 - (world_pos.y / 360.0);
+    // Convert to clip space, stretching the viewport across the target
+    let clip_x = (world_pos.x / globals.viewport.x) * 2.0 - 1.0;
+    let clip_y = 1.0 - (world_pos.y / globals.viewport.y) * 2.0;
     
     out.clip_position = vec4<f32>(clip_x, clip_y, 0.0, 1.0);
     out.color = instance.color;
-    out.uv = vertex.position; // -1 to 1 range
+    out.uv = vertex.position; // -1 to 1 range, turns with the quad
+    out.frame = instance.frame;
     
     return out;
 }
 
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    // Frame cell of the atlas, numbered row by row
+    let frame = in.frame % (globals.atlas_grid.x * globals.atlas_grid.y);
+    let cell = vec2<u32>(frame % globals.atlas_grid.x, frame / globals.atlas_grid.x);
+    let atlas_uv = (vec2<f32>(cell) + in.uv * 0.5 + 0.5) / vec2<f32>(globals.atlas_grid);
+    let texel = textureSample(atlas, atlas_sampler, atlas_uv);
+
+    if (globals.use_atlas == 1u) {
+        let sprite = texel * in.color;
+        if (sprite.a < 0.01) {
+            discard;
+        }
+        return sprite;
+    }
+
     // Create circular particles using distance from center
     let dist = length(in.uv);
     
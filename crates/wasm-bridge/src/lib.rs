@@ -2,8 +2,8 @@
 //! 
 //! This crate provides the JavaScript API for the particle system
 
-use particle_core::{ParticleSystem, Emitter, Force, Outline};
-use renderer::{Renderer, ParticleRenderer};
+use particle_core::{Curve, EdgeMode, EmitterId, EmitterShape, ParticleEvent, ParticleSystem, Emitter, Force, Outline, Scene, WorldBounds, AABB};
+use renderer::{Renderer, ParticleRenderer, SpriteAtlas};
 use wasm_bindgen::prelude::*;
 
 #[wasm_bindgen]
@@ -16,7 +16,54 @@ macro_rules! console_log {
     ($($t:tt)*) => (log(&format_args!($($t)*).to_string()))
 }
 
+/// Seconds an emitter added on click keeps emitting
+const CLICK_EMITTER_DURATION: f32 = 3.0;
+
+/// Floats per event in the array returned by `App::drain_events`
+const EVENT_STRIDE: usize = 9;
+
+/// Scene shown until another one is loaded
+const DEFAULT_SCENE: &str = include_str!("../../../scenes/default.json");
+
+/// Creates an emitter whose particles fade out over their lifetime
+fn fading_emitter(x: f32, y: f32) -> Emitter {
+    let mut emitter = Emitter::new(glam::Vec2::new(x, y));
+    emitter.settings.alpha_over_life = Some(Curve::linear(1.0, 0.0));
+    emitter
+}
+
+/// Builds a scene with its emitters' particles already on screen
+fn start_scene(scene: &Scene) -> (ParticleSystem, Vec<Force>) {
+    let (mut particle_system, forces) = scene.build();
+    let lifetime = particle_system
+        .emitters
+        .iter()
+        .map(|emitter| emitter.settings.particle_lifetime)
+        .fold(0.0, f32::max);
+    particle_system.prewarm(lifetime, &forces);
+    (particle_system, forces)
+}
+
+/// Fits the world bounds to the canvas, keeping their edge modes
+///
+/// Particles leaving a canvas without configured bounds are killed so they
+/// stop taking up the particle budget. Zero sizes, e.g. of a hidden canvas,
+/// are ignored like the renderer does.
+fn fit_bounds(particle_system: &mut ParticleSystem, width: u32, height: u32) {
+    if width == 0 || height == 0 {
+        return;
+    }
+    let area = AABB::new(glam::Vec2::ZERO, glam::Vec2::new(width as f32, height as f32));
+    match &mut particle_system.config.bounds {
+        Some(bounds) => bounds.area = area,
+        None => particle_system.config.bounds = Some(WorldBounds::new(area, EdgeMode::Kill)),
+    }
+}
+
 /// Main application state
+///
+/// Positions passed in and out are in canvas pixels, the space shared by
+/// the world bounds and the particle renderer.
 #[wasm_bindgen]
 pub struct App {
     particle_system: ParticleSystem,
@@ -24,6 +71,14 @@ pub struct App {
     particle_renderer: Option<ParticleRenderer>,
     forces: Vec<Force>,
     outline: Option<Outline>,
+    /// Emitter spawning along the outline, if enabled
+    outline_emitter: Option<EmitterId>,
+    /// Disabled emitter used for one-shot bursts
+    burst_emitter: Option<EmitterId>,
+    /// Scene the running system was started from
+    scene: Scene,
+    /// Seed set from JavaScript, replacing that of every scene started
+    seed: Option<u64>,
     last_time: f64,
 }
 
@@ -35,16 +90,9 @@ impl App {
         console_error_panic_hook::set_once();
         console_log!("Initializing Movement Particles...");
 
-        let mut particle_system = ParticleSystem::new();
-        
-        // Add a default emitter
-        let emitter = Emitter::new(glam::Vec2::new(640.0, 360.0));
-        particle_system.add_emitter(emitter);
-
-        // Add default forces
-        let forces = vec![
-            Force::gravity(0.0, 50.0),
-        ];
+        // Fixed timestep, emitter limit and default emitter come from the scene
+        let scene = Scene::from_json(DEFAULT_SCENE).expect("default scene is valid");
+        let (particle_system, forces) = start_scene(&scene);
 
         Self {
             particle_system,
@@ -52,6 +100,10 @@ impl App {
             particle_renderer: None,
             forces,
             outline: None,
+            outline_emitter: None,
+            burst_emitter: None,
+            scene,
+            seed: None,
             last_time: 0.0,
         }
     }
@@ -115,6 +167,7 @@ impl App {
 
         let particle_renderer = ParticleRenderer::new(
             &renderer.device,
+            &renderer.queue,
             renderer.config.format,
             self.particle_system.config.max_particles,
         );
@@ -123,6 +176,7 @@ impl App {
 
         self.renderer = Some(renderer);
         self.particle_renderer = Some(particle_renderer);
+        self.fit_canvas(width, height);
 
         console_log!("✓ Renderer initialized successfully!");
         Ok(())
@@ -139,7 +193,7 @@ impl App {
         self.last_time = timestamp;
 
         // Update particle system
-        self.particle_system.update(dt as f32, &self.forces);
+        self.particle_system.advance(dt as f32, &self.forces);
     }
 
     /// Renders the current frame
@@ -172,6 +226,7 @@ impl App {
             &view,
             &renderer.queue,
             &self.particle_system.particles,
+            self.particle_system.interpolation_alpha(),
         );
 
         renderer.queue.submit(std::iter::once(encoder.finish()));
@@ -185,6 +240,13 @@ impl App {
     pub fn update_outline(&mut self, points: &[f32]) {
         if points.len() < 4 {
             self.outline = None;
+            self.particle_system.colliders.clear();
+            if let Some(emitter) = self
+                .outline_emitter
+                .and_then(|id| self.particle_system.emitter_mut(id))
+            {
+                emitter.settings.enabled = false;
+            }
             return;
         }
 
@@ -193,17 +255,69 @@ impl App {
             .map(|chunk| glam::Vec2::new(chunk[0], chunk[1]))
             .collect();
 
-        self.outline = Some(Outline::from_points(outline_points));
-        
+        let outline = Outline::from_points(outline_points);
+
+        if let Some(emitter) = self
+            .outline_emitter
+            .and_then(|id| self.particle_system.emitter_mut(id))
+        {
+            emitter.settings.shape = EmitterShape::Outline(outline.clone());
+            emitter.settings.enabled = emitter.settings.rate > 0.0;
+        }
+
+        // Particles bounce off the silhouette
+        self.particle_system.colliders = vec![outline.clone()];
+        self.outline = Some(outline);
+
         // TODO: Add outline-based forces to self.forces
     }
 
-    /// Resizes the renderer
+    /// Draws particles as frames of an RGBA8 sprite atlas
+    ///
+    /// `pixels` holds `width * height` RGBA pixels split into `columns` by
+    /// `rows` frames, numbered row by row. Fails if any of the four is zero.
+    #[wasm_bindgen]
+    pub fn set_sprite_atlas(
+        &mut self,
+        pixels: &[u8],
+        width: u32,
+        height: u32,
+        columns: u32,
+        rows: u32,
+    ) -> Result<(), JsValue> {
+        if width == 0 || height == 0 || columns == 0 || rows == 0 {
+            return Err(JsValue::from_str("Atlas dimensions and frame grid must not be zero"));
+        }
+        let expected = (width as usize)
+            .checked_mul(height as usize)
+            .and_then(|area| area.checked_mul(4));
+        if expected != Some(pixels.len()) {
+            return Err(JsValue::from_str("Atlas size does not match its dimensions"));
+        }
+
+        let renderer = self.renderer.as_ref().ok_or("Renderer not initialized")?;
+        let particle_renderer = self.particle_renderer.as_mut().ok_or("Particle renderer not initialized")?;
+        particle_renderer.set_sprite_atlas(
+            &renderer.device,
+            &renderer.queue,
+            &SpriteAtlas {
+                pixels,
+                width,
+                height,
+                columns,
+                rows,
+            },
+        );
+        Ok(())
+    }
+
+    /// Resizes the renderer and fits the world bounds to the canvas
     #[wasm_bindgen]
     pub fn resize(&mut self, width: u32, height: u32) {
         if let Some(renderer) = &mut self.renderer {
             renderer.resize(width, height);
         }
+        self.fit_canvas(width, height);
     }
 
     /// Returns the current particle count
@@ -212,10 +326,296 @@ impl App {
         self.particle_system.particle_count()
     }
 
-    /// Adds an emitter at the given position
+    /// Returns how many particles each emitter dropped for lack of room
+    #[wasm_bindgen]
+    pub fn dropped_counts(&self) -> Vec<u32> {
+        self.particle_system
+            .emitters
+            .iter()
+            .map(|emitter| emitter.dropped().min(u32::MAX as u64) as u32)
+            .collect()
+    }
+
+    /// Returns the statistics of the last simulation step for the HUD
+    ///
+    /// Layout: `spawned, died, dropped, particles, average_speed,
+    /// kinetic_energy`, then the milliseconds spent on `forces,
+    /// integration, collision, emission` and the whole step.
+    #[wasm_bindgen]
+    pub fn step_stats(&self) -> Vec<f32> {
+        let stats = self.particle_system.stats();
+        let timings = &stats.timings;
+        let ms = |duration: std::time::Duration| duration.as_secs_f32() * 1000.0;
+        vec![
+            stats.spawned as f32,
+            stats.died as f32,
+            stats.dropped as f32,
+            stats.particles as f32,
+            stats.average_speed,
+            stats.kinetic_energy,
+            ms(timings.forces),
+            ms(timings.integration),
+            ms(timings.collision),
+            ms(timings.emission),
+            ms(timings.total),
+        ]
+    }
+
+    /// Returns the live particles of each emitter after the last step
+    #[wasm_bindgen]
+    pub fn emitter_particle_counts(&self) -> Vec<u32> {
+        self.particle_system
+            .stats()
+            .emitter_particles
+            .iter()
+            .map(|&count| count as u32)
+            .collect()
+    }
+
+    /// Emits particles along the detected outline at the given rate
+    ///
+    /// Particles leave the silhouette along its outward normal. A rate of
+    /// zero turns outline emission off.
+    #[wasm_bindgen]
+    pub fn set_outline_emission(&mut self, rate: f32) {
+        if self.outline_emitter.is_none() {
+            let mut emitter = fading_emitter(0.0, 0.0);
+            emitter.settings.velocity_along_normal = true;
+            emitter.settings.spread = 0.2;
+            self.outline_emitter = self.particle_system.add_emitter(emitter);
+        }
+
+        let Some(emitter) = self
+            .outline_emitter
+            .and_then(|id| self.particle_system.emitter_mut(id))
+        else {
+            console_log!("No room for the outline emitter");
+            return;
+        };
+        emitter.settings.rate = rate;
+        emitter.settings.enabled = rate > 0.0 && self.outline.is_some();
+        if let Some(outline) = &self.outline {
+            emitter.settings.shape = EmitterShape::Outline(outline.clone());
+        }
+    }
+
+    /// Restarts the running scene from a seed, making the run reproducible
+    ///
+    /// The scene's particles are prewarmed again from the seed, and scenes
+    /// loaded later use it too. Emitters added at runtime are dropped, as
+    /// by `load_scene`.
+    #[wasm_bindgen]
+    pub fn set_seed(&mut self, seed: u32) {
+        self.seed = Some(seed as u64);
+        self.scene.config.seed = self.seed;
+        self.restart();
+    }
+
+    /// Spawns `count` particles at the given position right away
+    ///
+    /// Particles fly out in every direction, e.g. for a firework on click.
+    /// Returns the number spawned, which is capped by the particle budget.
+    #[wasm_bindgen]
+    pub fn burst(&mut self, x: f32, y: f32, count: u32) -> usize {
+        if self.burst_emitter.is_none() {
+            let mut emitter = fading_emitter(x, y);
+            emitter.settings.enabled = false;
+            emitter.settings.spread = std::f32::consts::PI;
+            emitter.settings.initial_velocity = 150.0;
+            emitter.settings.particle_lifetime = 2.0;
+            self.burst_emitter = self.particle_system.add_emitter(emitter);
+        }
+
+        let Some(id) = self.burst_emitter else {
+            return 0;
+        };
+        if let Some(emitter) = self.particle_system.emitter_mut(id) {
+            emitter.teleport(glam::Vec2::new(x, y));
+        }
+        self.particle_system.burst(id, count).unwrap_or(0)
+    }
+
+    /// Adds an emitter at the given position and returns its handle
+    ///
+    /// The emitter runs for a few seconds and is removed once its particles
+    /// have faded. Returns nothing if the emitter limit has been reached.
+    #[wasm_bindgen]
+    pub fn add_emitter(&mut self, x: f32, y: f32) -> Option<u32> {
+        let mut emitter = fading_emitter(x, y);
+        emitter.settings.duration = Some(CLICK_EMITTER_DURATION);
+        self.particle_system
+            .add_emitter(emitter)
+            .map(EmitterId::to_bits)
+    }
+
+    /// Stops an emitter, letting its particles fade before it is removed
+    #[wasm_bindgen]
+    pub fn stop_emitter(&mut self, id: u32) -> bool {
+        self.particle_system.stop_emitter(EmitterId::from_bits(id))
+    }
+
+    /// Removes an emitter and its particles right away
+    #[wasm_bindgen]
+    pub fn remove_emitter(&mut self, id: u32) -> bool {
+        self.particle_system
+            .remove_emitter(EmitterId::from_bits(id))
+            .is_some()
+    }
+
+    /// Replaces the running scene with one written as JSON
+    ///
+    /// Emitters added at runtime are dropped; the detected outline stays.
+    /// A seed given to `set_seed` replaces the scene's own. On error the
+    /// current scene keeps running and the returned message gives the line
+    /// and column of the problem.
+    #[wasm_bindgen]
+    pub fn load_scene(&mut self, source: &str) -> Result<(), JsValue> {
+        let mut scene = Scene::from_json(source).map_err(|e| {
+            let err_msg = format!("Failed to load scene: {}", e);
+            console_log!("ERROR: {}", err_msg);
+            JsValue::from_str(&err_msg)
+        })?;
+
+        if self.seed.is_some() {
+            scene.config.seed = self.seed;
+        }
+        self.scene = scene;
+        self.restart();
+        Ok(())
+    }
+
+    /// Returns the running scene as JSON, e.g. to save a tweaked look
+    #[wasm_bindgen]
+    pub fn export_scene(&self) -> String {
+        self.particle_system.to_scene(&self.forces).to_json()
+    }
+
+    /// Turns recording of particle events on or off
+    ///
+    /// Recorded events pile up until drained, so call `drain_events` every
+    /// frame while recording.
+    #[wasm_bindgen]
+    pub fn set_event_recording(&mut self, enabled: bool) {
+        self.particle_system.config.record_events = enabled;
+        if !enabled {
+            self.particle_system.drain_events();
+        }
+    }
+
+    /// Adds a zone that reports particles entering and leaving it
+    ///
+    /// `points` holds the polygon as `x, y` pairs. Returns the zone index
+    /// used in events.
+    #[wasm_bindgen]
+    pub fn add_zone(&mut self, points: &[f32]) -> usize {
+        let points = points
+            .chunks_exact(2)
+            .map(|chunk| glam::Vec2::new(chunk[0], chunk[1]))
+            .collect();
+        self.particle_system.zones.push(Outline::from_points(points));
+        self.particle_system.zones.len() - 1
+    }
+
+    /// Removes all zones
+    #[wasm_bindgen]
+    pub fn clear_zones(&mut self) {
+        self.particle_system.zones.clear();
+    }
+
+    /// Returns the events recorded since the last call as a flat array
+    ///
+    /// Each event takes 9 floats: `kind, particle, source, x, y, nx, ny,
+    /// speed, generation`. `kind` is 0 for spawned, 1 for died, 2 for
+    /// collided, 3 for entered and 4 for left. `particle` is the particle's
+    /// slot index and `generation` tells apart the particles that reuse the
+    /// slot, even within one update.
+    /// `source` is the emitter handle (-1 if none) for spawns and deaths,
+    /// the collider index for collisions and the zone index otherwise.
+    /// `nx, ny` and `speed` are the surface normal and impact speed of
+    /// collisions and 0 for other events.
+    #[wasm_bindgen]
+    pub fn drain_events(&mut self) -> Vec<f32> {
+        let emitter = |id: Option<EmitterId>| id.map_or(-1.0, |id| id.to_bits() as f32);
+        let mut out = Vec::with_capacity(self.particle_system.events().len() * EVENT_STRIDE);
+        for event in self.particle_system.drain_events() {
+            let (kind, source, normal, speed) = match event {
+                ParticleEvent::Spawned { emitter: id, .. } => (0.0, emitter(id), glam::Vec2::ZERO, 0.0),
+                ParticleEvent::Died { emitter: id, .. } => (1.0, emitter(id), glam::Vec2::ZERO, 0.0),
+                ParticleEvent::Collided { collider, normal, speed, .. } => (2.0, collider as f32, normal, speed),
+                ParticleEvent::Entered { zone, .. } => (3.0, zone as f32, glam::Vec2::ZERO, 0.0),
+                ParticleEvent::Left { zone, .. } => (4.0, zone as f32, glam::Vec2::ZERO, 0.0),
+            };
+            let position = event.position();
+            out.extend_from_slice(&[
+                kind,
+                event.particle().index() as f32,
+                source,
+                position.x,
+                position.y,
+                normal.x,
+                normal.y,
+                speed,
+                event.particle().generation() as f32,
+            ]);
+        }
+        out
+    }
+
+    /// Returns the number of emitters, including finished ones still fading
+    #[wasm_bindgen]
+    pub fn emitter_count(&self) -> usize {
+        self.particle_system.emitters.len()
+    }
+
+    /// Moves an emitter, e.g. one attached to a tracked hand
+    ///
+    /// Particles spawn along the path since the last frame and take on
+    /// `inherit_velocity` of the emitter's motion, so fast moves leave a
+    /// continuous trail.
     #[wasm_bindgen]
-    pub fn add_emitter(&mut self, x: f32, y: f32) {
-        let emitter = Emitter::new(glam::Vec2::new(x, y));
-        self.particle_system.add_emitter(emitter);
+    pub fn move_emitter(&mut self, id: u32, x: f32, y: f32, inherit_velocity: f32) {
+        if let Some(emitter) = self.particle_system.emitter_mut(EmitterId::from_bits(id)) {
+            emitter.settings.position = glam::Vec2::new(x, y);
+            emitter.settings.inherit_velocity = inherit_velocity;
+        }
+    }
+}
+
+impl App {
+    /// Replaces the particle system with a fresh start of the scene
+    ///
+    /// Keeps the detected outline, the zones and whether events are
+    /// recorded, none of which apply while prewarming. Fits the bounds to
+    /// the canvas and sizes the particle renderer for the scene's particle
+    /// limit.
+    fn restart(&mut self) {
+        let (mut particle_system, forces) = start_scene(&self.scene);
+        particle_system.colliders = self.outline.iter().cloned().collect();
+        particle_system.zones = std::mem::take(&mut self.particle_system.zones);
+        particle_system.config.record_events = self.particle_system.config.record_events;
+        if let Some(renderer) = &self.renderer {
+            if let Some(particle_renderer) = &mut self.particle_renderer {
+                particle_renderer.set_max_particles(&renderer.device, particle_system.config.max_particles);
+            }
+        }
+        self.particle_system = particle_system;
+        self.forces = forces;
+        self.outline_emitter = None;
+        self.burst_emitter = None;
+        if let Some(renderer) = &self.renderer {
+            let (width, height) = (renderer.config.width, renderer.config.height);
+            self.fit_canvas(width, height);
+        }
+    }
+
+    /// Fits the world bounds and the area the particles are drawn from to the canvas
+    ///
+    /// Both span the canvas in its pixels, so particles reach the bounds
+    /// exactly where they reach the edge of the canvas.
+    fn fit_canvas(&mut self, width: u32, height: u32) {
+        fit_bounds(&mut self.particle_system, width, height);
+        if let Some(particle_renderer) = &mut self.particle_renderer {
+            particle_renderer.set_viewport(width as f32, height as f32);
+        }
     }
 }
@@ -106,9 +106,10 @@ class Application {
     // Add emitter on click
     this.canvas.addEventListener('click', (e) => {
       if (this.app) {
+        // The simulation works in canvas pixels, which differ from CSS pixels on HiDPI screens
         const rect = this.canvas.getBoundingClientRect();
-        const x = e.clientX - rect.left;
-        const y = e.clientY - rect.top;
+        const x = (e.clientX - rect.left) * (this.canvas.width / rect.width);
+        const y = (e.clientY - rect.top) * (this.canvas.height / rect.height);
         this.app.add_emitter(x, y);
       }
     });